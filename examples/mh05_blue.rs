@@ -1,34 +1,27 @@
 extern crate puzzletools;
 
+use puzzletools::code::binary::{binary_letter, read_bits_filtered};
 use puzzletools::search::print_result;
 use puzzletools::word::Text;
 use puzzletools::wordlist::load_wordlist_iter;
 
-fn binary<S: Text>(v: S) -> Option<u8> {
-    let mut ans: u64 = 0;
-    for c in v.bytes() {
-        match c {
-            b'O' => ans <<= 1,
-            b'I' => {
-                ans <<= 1;
-                ans += 1;
-            }
-            b'A' | b'E' | b'U' | b'Y' => return None,
-            _ => {}
-        };
-    }
-    if (1..=26).contains(&ans) {
-        Some((ans as u8) + b'@')
-    } else {
-        None
+// reads O as 0 and I as 1, in words with no other vowels
+fn binary<S: Text>(v: S) -> Option<char> {
+    if v.bytes().any(|c| matches!(c, b'A' | b'E' | b'U' | b'Y')) {
+        return None;
     }
+    binary_letter(read_bits_filtered(v, |c| match c {
+        b'O' => Some(false),
+        b'I' => Some(true),
+        _ => None,
+    }))
 }
 
 fn main() {
     let it = load_wordlist_iter("combined.freq.txt").unwrap();
     it.filter(|w| {
         let s = w.slug();
-        binary(&s) == Some(b'W') && s.char_1_eq(6, 'I')
+        binary(&s) == Some('W') && s.char_1_eq(6, 'I')
     })
     .take(50)
     .for_each(print_result);
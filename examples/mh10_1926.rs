@@ -1,5 +1,5 @@
 use puzzletools::search::print_result;
-use puzzletools::word::{alphagram, Text};
+use puzzletools::word::{is_anagram, Text};
 use puzzletools::wordlist::load_wordlist_iter;
 
 fn main() {
@@ -15,7 +15,7 @@ fn main() {
             .zip(s.bytes())
             .filter_map(|(a, b)| if a == b { Some(a) } else { None })
             .collect();
-        matches.len() == 4 && alphagram(matches) == "ADIT"
+        matches.len() == 4 && is_anagram(matches, "ADIT")
     })
     .take(50)
     .for_each(print_result);
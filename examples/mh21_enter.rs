@@ -1,25 +1,30 @@
 use puzzletools::letter::lett_to_num_0;
 use puzzletools::search::{print_result, sort_results};
-use puzzletools::word::{deleted_letter_iter, Text};
+use puzzletools::word::{deleted_letter_iter, map_word, Text};
 use puzzletools::wordlist::{pairs_iter, Wordlist};
 
 const FLIPPED: &[u8] = b"EB@PA@@YIR@TWUOD@JSLN@MXHZ";
 
-fn transform_one(c: char) -> char {
-    FLIPPED[lett_to_num_0(c)] as char
+// The letters as they look upside down in the font used by the puzzle,
+// which differs from `letter::upside_down`.
+fn transform_one(c: char) -> Option<char> {
+    match FLIPPED[lett_to_num_0(c)] {
+        b'@' => None,
+        f => Some(f as char),
+    }
 }
 
-fn transform(s: String) -> String {
-    s.chars().rev().map(transform_one).collect()
+fn transform(s: String) -> Option<String> {
+    map_word(s.reversed(), transform_one)
 }
 
 fn main() {
     let wl = Wordlist::load("combined.freq.txt").unwrap();
-    let goal = transform_one('W');
+    let goal = transform_one('W').unwrap();
     let it = pairs_iter(wl.iter().filter(|w| w.len() >= 5), &wl, |w| {
         deleted_letter_iter(&w.slug).filter_map(|i| {
             if i.deleted_char() == goal {
-                Some(transform(i.text()))
+                transform(i.text())
             } else {
                 None
             }
@@ -0,0 +1,534 @@
+//! Classical ciphers, and tools for breaking them.
+//!
+//! Encryption and decryption functions only change letters.  Lowercase
+//! letters stay lowercase, and all other characters are left alone.
+//!
+//! Functions that break ciphers rank the possible plaintexts using a
+//! `Scorer`.  A `Wordlist` can be used as a scorer (it scores a text by
+//! splitting it into words), as can an `NgramScorer` built from a
+//! wordlist.
+
+pub mod analysis;
+pub mod cryptogram;
+pub mod enigma;
+pub mod polybius;
+pub mod polygraphic;
+pub mod square;
+pub mod transposition;
+pub mod vigenere;
+
+use crate::search::{sort_results, Score, SearchResult};
+use crate::word::Text;
+use crate::wordlist::Wordlist;
+use std::fmt::{self, Display};
+
+/// Applies `f` to each letter of `s`, where letters are represented by
+/// numbers from 0 to 25, preserving case.
+pub(crate) fn map_letters<S: Text, F: FnMut(usize) -> usize>(s: S, mut f: F) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_uppercase() {
+                (b'A' + f((c as u8 - b'A') as usize) as u8) as char
+            } else if c.is_ascii_lowercase() {
+                (b'a' + f((c as u8 - b'a') as usize) as u8) as char
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// A small deterministic random number generator (xorshift64*), used by
+/// the randomized search functions so that their results are repeatable.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a random number less than `n`.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Returns the letters of `s`, converted to uppercase, with everything
+/// else removed.
+pub(crate) fn letters<S: Text>(s: S) -> Vec<u8> {
+    s.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Shifts each letter forward in the alphabet by `shift` places.
+///
+/// To find pairs of words that are Caesar shifts of each other:
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::caesar_shift;
+/// use puzzletools::wordlist::{Wordlist, pairs_iter};
+/// let wl = Wordlist::load_from_reader(Cursor::new("CHEER,1\nJOLLY,1\nMERRY,1")).unwrap();
+/// let v: Vec<_> = pairs_iter(wl.iter(), &wl, |w| {
+///     let s = w.slug.clone();
+///     (1..26).map(move |n| caesar_shift(&s, n))
+/// })
+/// .map(|(w1, w2)| (w1.word.clone(), w2.word.clone()))
+/// .collect();
+/// assert_eq!(v, vec![("CHEER".to_string(), "JOLLY".to_string()), ("JOLLY".to_string(), "CHEER".to_string())]);
+/// ```
+pub fn caesar_shift<S: Text>(s: S, shift: usize) -> String {
+    map_letters(s, |n| (n + shift) % 26)
+}
+
+/// Applies ROT13.
+/// ```
+/// use puzzletools::cipher::rot13;
+/// assert_eq!(rot13("Hello, World!"), "Uryyb, Jbeyq!");
+/// ```
+pub fn rot13<S: Text>(s: S) -> String {
+    caesar_shift(s, 13)
+}
+
+/// Applies the Atbash cipher, which reverses the alphabet.
+/// ```
+/// use puzzletools::cipher::atbash;
+/// assert_eq!(atbash("WIZARD"), "DRAZIW");
+/// ```
+pub fn atbash<S: Text>(s: S) -> String {
+    map_letters(s, |n| 25 - n)
+}
+
+/// An affine cipher, which sends the letter with number `x` (starting
+/// from A = 0) to the letter with number `a * x + b` (mod 26).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Affine {
+    a: usize,
+    b: usize,
+}
+
+impl Affine {
+    /// Creates an affine cipher.  Returns `None` if `a` is not relatively
+    /// prime to 26.
+    pub fn new(a: usize, b: usize) -> Option<Self> {
+        if a % 2 == 1 && !a.is_multiple_of(13) {
+            Some(Self {
+                a: a % 26,
+                b: b % 26,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns all 312 affine ciphers.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (1..26).flat_map(|a| (0..26).filter_map(move |b| Self::new(a, b)))
+    }
+
+    /// ```
+    /// use puzzletools::cipher::Affine;
+    /// assert_eq!(Affine::new(5, 8).unwrap().encrypt("AFFINE cipher"), "IHHWVC swfrcp");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        map_letters(s, |n| (self.a * n + self.b) % 26)
+    }
+
+    /// ```
+    /// use puzzletools::cipher::Affine;
+    /// assert_eq!(Affine::new(5, 8).unwrap().decrypt("IHHWVC swfrcp"), "AFFINE cipher");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let inv = (1..26).find(|i| (i * self.a) % 26 == 1).unwrap();
+        map_letters(s, |n| (inv * (n + 26 - self.b)) % 26)
+    }
+}
+
+impl Display for Affine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a={}, b={}", self.a, self.b)
+    }
+}
+
+/// A monoalphabetic substitution cipher.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Substitution {
+    // the ciphertext letter for each plaintext letter
+    forward: [u8; 26],
+    // the plaintext letter for each ciphertext letter
+    backward: [u8; 26],
+}
+
+impl Substitution {
+    /// Creates a substitution cipher from the ciphertext alphabet, that
+    /// is, the letters that A through Z are sent to.  Returns `None` if
+    /// the alphabet is not a permutation of the letters.
+    /// ```
+    /// use puzzletools::cipher::Substitution;
+    /// let s = Substitution::from_alphabet("ZYXWVUTSRQPONMLKJIHGFEDCBA").unwrap();
+    /// assert_eq!(s.encrypt("WIZARD"), "DRAZIW");
+    /// assert!(Substitution::from_alphabet("AABC").is_none());
+    /// ```
+    pub fn from_alphabet<S: Text>(alphabet: S) -> Option<Self> {
+        let v = letters(alphabet);
+        if v.len() != 26 {
+            return None;
+        }
+        let mut forward = [0; 26];
+        let mut backward = [0xFF; 26];
+        for (n, &c) in v.iter().enumerate() {
+            let idx = (c - b'A') as usize;
+            if backward[idx] != 0xFF {
+                return None;
+            }
+            forward[n] = idx as u8;
+            backward[idx] = n as u8;
+        }
+        Some(Self { forward, backward })
+    }
+
+    /// Creates a keyword cipher: the ciphertext alphabet is the keyword
+    /// (with repeated letters removed), followed by the remaining letters
+    /// in alphabetical order.
+    /// ```
+    /// use puzzletools::cipher::Substitution;
+    /// let s = Substitution::from_keyword("ZEBRAS");
+    /// assert_eq!(s.to_string(), "ZEBRASCDFGHIJKLMNOPQTUVWXY");
+    /// assert_eq!(s.encrypt("Flee at once"), "Siaa zq lkba");
+    /// ```
+    pub fn from_keyword<S: Text>(keyword: S) -> Self {
+        Self::from_alphabet(keyword_alphabet(keyword)).unwrap()
+    }
+
+    /// Returns the substitution cipher corresponding to a Caesar shift.
+    pub fn caesar(shift: usize) -> Self {
+        Self::from_alphabet(caesar_shift("ABCDEFGHIJKLMNOPQRSTUVWXYZ", shift)).unwrap()
+    }
+
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        map_letters(s, |n| self.forward[n] as usize)
+    }
+
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        map_letters(s, |n| self.backward[n] as usize)
+    }
+
+    /// Returns the cipher that undoes this one.
+    pub fn inverse(&self) -> Self {
+        Self {
+            forward: self.backward,
+            backward: self.forward,
+        }
+    }
+
+    /// Returns the ciphertext alphabet.
+    pub fn alphabet(&self) -> String {
+        self.forward.iter().map(|&n| (b'A' + n) as char).collect()
+    }
+}
+
+impl From<Affine> for Substitution {
+    fn from(a: Affine) -> Self {
+        Self::from_alphabet(a.encrypt("ABCDEFGHIJKLMNOPQRSTUVWXYZ")).unwrap()
+    }
+}
+
+impl Display for Substitution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.alphabet())
+    }
+}
+
+/// Returns the keyword (with repeated letters removed) followed by the
+/// remaining letters of the alphabet.
+/// ```
+/// use puzzletools::cipher::keyword_alphabet;
+/// assert_eq!(keyword_alphabet("Hello"), "HELOABCDFGIJKMNPQRSTUVWXYZ");
+/// ```
+pub fn keyword_alphabet<S: Text>(keyword: S) -> String {
+    let mut seen = [false; 26];
+    let mut v = String::with_capacity(26);
+    for c in letters(keyword).into_iter().chain(b'A'..=b'Z') {
+        let idx = (c - b'A') as usize;
+        if !seen[idx] {
+            seen[idx] = true;
+            v.push(c as char);
+        }
+    }
+    v
+}
+
+/// Returns the index of coincidence of the letters of `s`: the
+/// probability that two letters chosen at random from different
+/// positions are the same.  This is about 0.066 for English text and
+/// 0.038 for random letters.
+/// ```
+/// use puzzletools::cipher::index_of_coincidence;
+/// assert_eq!(index_of_coincidence("AABB"), 1.0 / 3.0);
+/// assert_eq!(index_of_coincidence("A"), 0.0);
+/// ```
+pub fn index_of_coincidence<S: Text>(s: S) -> f64 {
+    let mut counts = [0usize; 26];
+    let mut total = 0;
+    for c in s.bytes().filter(u8::is_ascii_alphabetic) {
+        counts[(c.to_ascii_uppercase() - b'A') as usize] += 1;
+        total += 1;
+    }
+    if total < 2 {
+        return 0.0;
+    }
+    let pairs: usize = counts.iter().map(|&n| n * n.saturating_sub(1)).sum();
+    pairs as f64 / (total * (total - 1)) as f64
+}
+
+/// Measures how much a sequence of uppercase letters looks like English.
+/// Higher scores are better.
+pub trait Scorer {
+    /// Scores a sequence of uppercase letters.
+    fn score_letters(&self, letters: &[u8]) -> f64;
+
+    /// Scores the letters of a text, ignoring case and non-letters.
+    fn score<S: Text>(&self, s: S) -> f64 {
+        self.score_letters(&letters(s))
+    }
+}
+
+impl Scorer for Wordlist {
+    fn score_letters(&self, letters: &[u8]) -> f64 {
+        self.segmentation_score(letters)
+    }
+}
+
+impl<T: Scorer> Scorer for &T {
+    fn score_letters(&self, letters: &[u8]) -> f64 {
+        (*self).score_letters(letters)
+    }
+}
+
+/// Scores text by the log (base 10) probabilities of its n-grams
+/// (sequences of `n` consecutive letters).
+pub struct NgramScorer {
+    n: usize,
+    log_probs: Vec<f32>,
+}
+
+impl NgramScorer {
+    /// Creates a scorer using the n-grams that appear in the words of a
+    /// wordlist, weighted by frequency.  `n` must be between 1 and 5.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::cipher::{NgramScorer, Scorer};
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("THE,100\nTHERE,20\nHERE,10")).unwrap();
+    /// let scorer = NgramScorer::from_wordlist(&wl, 2);
+    /// assert!(scorer.score("there") > scorer.score("xqzjv"));
+    /// ```
+    pub fn from_wordlist(wl: &Wordlist, n: usize) -> Self {
+        assert!((1..=5).contains(&n), "n must be between 1 and 5");
+        let size = 26usize.pow(n as u32);
+        let mut counts = vec![0f64; size];
+        for e in wl {
+            for w in e.slug.as_bytes().windows(n) {
+                if let Some(idx) = ngram_index(w) {
+                    counts[idx] += e.freq as f64;
+                }
+            }
+        }
+        let total: f64 = counts.iter().sum::<f64>().max(1.0);
+        let floor = (0.01 / total).log10() as f32;
+        let log_probs = counts
+            .into_iter()
+            .map(|c| {
+                if c > 0.0 {
+                    (c / total).log10() as f32
+                } else {
+                    floor
+                }
+            })
+            .collect();
+        Self { n, log_probs }
+    }
+
+    /// The length of the n-grams used by this scorer.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+}
+
+fn ngram_index(w: &[u8]) -> Option<usize> {
+    w.iter().try_fold(0, |acc, &c| {
+        if c.is_ascii_uppercase() {
+            Some(26 * acc + (c - b'A') as usize)
+        } else {
+            None
+        }
+    })
+}
+
+impl Scorer for NgramScorer {
+    fn score_letters(&self, letters: &[u8]) -> f64 {
+        letters
+            .windows(self.n)
+            .filter_map(ngram_index)
+            .map(|idx| self.log_probs[idx] as f64)
+            .sum()
+    }
+}
+
+/// A possible decryption of a ciphertext, along with the key that
+/// produced it and its score.
+#[derive(Clone, Debug)]
+pub struct Decryption<K> {
+    pub key: K,
+    pub plaintext: String,
+    pub score: f64,
+}
+
+impl<K: Display> SearchResult for Decryption<K> {
+    type Data = String;
+    type Freq = Score;
+    fn data(&self) -> String {
+        format!("{} ({})", self.plaintext, self.key)
+    }
+    fn freq(&self) -> Score {
+        Score(self.score)
+    }
+}
+
+/// Scores a list of possible decryptions, and returns them in order from
+/// best to worst.
+pub fn rank_decryptions<K, I, C>(candidates: I, scorer: &C) -> impl Iterator<Item = Decryption<K>>
+where
+    I: IntoIterator<Item = (K, String)>,
+    K: Display,
+    C: Scorer,
+{
+    sort_results(candidates.into_iter().map(|(key, plaintext)| {
+        let score = scorer.score(&plaintext);
+        Decryption {
+            key,
+            plaintext,
+            score,
+        }
+    }))
+}
+
+/// Tries all Caesar shifts.  The key of each decryption is the amount
+/// that the ciphertext was shifted backward.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::caesar_brute_force;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("ATTACK,5\nAT,10\nDAWN,3")).unwrap();
+/// let best = caesar_brute_force("DWWDFN DW GDZQ", &wl).next().unwrap();
+/// assert_eq!(best.key, 3);
+/// assert_eq!(best.plaintext, "ATTACK AT DAWN");
+/// ```
+pub fn caesar_brute_force<S: Text, C: Scorer>(
+    s: S,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<usize>> {
+    let candidates: Vec<_> = (0..26)
+        .map(|n| (n, caesar_shift(s.as_str(), 26 - n)))
+        .collect();
+    rank_decryptions(candidates, scorer)
+}
+
+/// Tries all affine ciphers.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::{affine_brute_force, Affine};
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("ATTACK,5\nAT,10\nDAWN,3")).unwrap();
+/// let best = affine_brute_force("IZZISG IZ XIOV", &wl).next().unwrap();
+/// assert_eq!(best.key, Affine::new(5, 8).unwrap());
+/// assert_eq!(best.plaintext, "ATTACK AT DAWN");
+/// ```
+pub fn affine_brute_force<S: Text, C: Scorer>(
+    s: S,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<Affine>> {
+    let candidates: Vec<_> = Affine::all().map(|a| (a, a.decrypt(s.as_str()))).collect();
+    rank_decryptions(candidates, scorer)
+}
+
+/// Tries a keyword cipher with each of the given keywords.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::keyword_brute_force;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("FLEE,5\nAT,10\nONCE,3\nZEBRAS,1")).unwrap();
+/// let best = keyword_brute_force("SIAA ZQ LKBA", wl.iter().map(|w| &w.slug), &wl).next().unwrap();
+/// assert_eq!(best.key, "ZEBRAS");
+/// assert_eq!(best.plaintext, "FLEE AT ONCE");
+/// ```
+pub fn keyword_brute_force<S, I, C>(
+    s: S,
+    keywords: I,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<String>>
+where
+    S: Text,
+    I: IntoIterator,
+    I::Item: Text,
+    C: Scorer,
+{
+    try_keys(
+        s,
+        keywords,
+        |k, s| Substitution::from_keyword(k).decrypt(s),
+        scorer,
+    )
+}
+
+/// Decrypts `s` with each of the given keys (for example, every entry of
+/// a wordlist), and ranks the resulting plaintexts.  The function
+/// `decrypt` is called with a key and the ciphertext.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::{try_keys, vigenere::Tableau};
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("ATTACK,5\nAT,10\nDAWN,3\nLEMON,1")).unwrap();
+/// let keys = wl.iter().map(|w| &w.slug);
+/// let decrypt = |k: &str, s: &str| Tableau::Vigenere.decrypt(s, k);
+/// let best = try_keys("LXFOPVEFRNHR", keys, decrypt, &wl).next().unwrap();
+/// assert_eq!(best.key, "LEMON");
+/// ```
+pub fn try_keys<S, I, F, C>(
+    s: S,
+    keys: I,
+    decrypt: F,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<String>>
+where
+    S: Text,
+    I: IntoIterator,
+    I::Item: Text,
+    F: Fn(&str, &str) -> String,
+    C: Scorer,
+{
+    let candidates: Vec<_> = keys
+        .into_iter()
+        .map(|k| (k.text_to_string(), decrypt(k.as_str(), s.as_str())))
+        .collect();
+    rank_decryptions(candidates, scorer)
+}
+
+#[test]
+fn substitution_roundtrip_test() {
+    let text = "The quick brown fox jumps over the lazy dog!";
+    for a in Affine::all() {
+        assert_eq!(a.decrypt(a.encrypt(text)), text);
+        assert_eq!(Substitution::from(a).encrypt(text), a.encrypt(text));
+    }
+    let s = Substitution::from_keyword("PUZZLE");
+    assert_eq!(s.inverse().encrypt(s.encrypt(text)), text);
+}
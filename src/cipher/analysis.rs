@@ -0,0 +1,310 @@
+//! Statistics for identifying the type of cipher used to produce a
+//! ciphertext.
+//!
+//! ```
+//! use puzzletools::cipher::analysis::{analyze, CipherFamily};
+//! let a = analyze("AABAB BAAAB ABBAB AABAA ABBBA");
+//! assert_eq!(a.guesses[0].0, CipherFamily::Baconian);
+//! let report = a.to_string();
+//! assert!(report.starts_with("Length: 25 symbols (25 letters), odd\nAlphabet size: 2\n"));
+//! assert!(report.contains("Symbol counts: A 14, B 11\n"));
+//! assert!(report.ends_with("Likely cipher types:\n  Baconian: 1.00\n"));
+//! ```
+
+use super::index_of_coincidence;
+use super::vigenere::{chi_squared, likely_periods};
+use crate::letter::lett_to_num_0;
+use crate::word::Text;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+/// A family of ciphers that produce ciphertexts with similar statistics.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CipherFamily {
+    /// A transposition cipher, which keeps the letter frequencies of
+    /// English.
+    Transposition,
+    /// A monoalphabetic substitution cipher, which keeps the index of
+    /// coincidence of English but not its letter frequencies.
+    Monoalphabetic,
+    /// A Vigenère cipher or another polyalphabetic cipher, which flattens
+    /// the letter frequencies.
+    Polyalphabetic,
+    /// Playfair and other digraphic ciphers with a 25 letter square.
+    Playfair,
+    /// A Polybius square, tap code, or ADFGVX, which use a handful of
+    /// symbols in pairs.
+    Polybius,
+    /// The Baconian cipher, which uses two symbols in groups of five.
+    Baconian,
+}
+
+impl Display for CipherFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CipherFamily::Transposition => "transposition",
+            CipherFamily::Monoalphabetic => "monoalphabetic substitution",
+            CipherFamily::Polyalphabetic => "polyalphabetic (Vigenère-like)",
+            CipherFamily::Playfair => "Playfair-like digraphic",
+            CipherFamily::Polybius => "Polybius square",
+            CipherFamily::Baconian => "Baconian",
+        })
+    }
+}
+
+/// Statistics of a ciphertext.  Symbols are the letters and digits of the
+/// ciphertext, converted to uppercase; everything else is ignored.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    /// The number of symbols.
+    pub length: usize,
+    /// The number of symbols that are letters.
+    pub letters: usize,
+    /// The distinct symbols, from most to least common, with their counts.
+    pub symbol_counts: Vec<(char, usize)>,
+    /// The letters that do not appear.
+    pub missing_letters: String,
+    /// The index of coincidence of the letters.
+    pub index_of_coincidence: f64,
+    /// The chi-squared statistic comparing the letter frequencies to
+    /// English.  This is small (below about 50 for a few hundred letters)
+    /// when the letters have English frequencies.
+    pub chi_squared: f64,
+    /// The most common bigrams of symbols, with their counts.
+    pub bigram_counts: Vec<(String, usize)>,
+    /// The number of places where a symbol is followed by the same symbol.
+    pub doubled_symbols: usize,
+    /// The number of pairs (symbols 1 and 2, 3 and 4, and so on) that
+    /// consist of the same symbol twice.
+    pub doubled_pairs: usize,
+    /// The periods that are most likely for a periodic cipher, with the
+    /// average index of coincidence of their columns.
+    pub periods: Vec<(usize, f64)>,
+    /// The cipher families, from most to least likely, with a rough
+    /// plausibility between 0 and 1.
+    pub guesses: Vec<(CipherFamily, f64)>,
+}
+
+impl Analysis {
+    /// The number of distinct symbols.
+    pub fn alphabet_size(&self) -> usize {
+        self.symbol_counts.len()
+    }
+
+    /// Whether the number of symbols is even.
+    pub fn is_even_length(&self) -> bool {
+        self.length.is_multiple_of(2)
+    }
+
+    fn guess(&self) -> Vec<(CipherFamily, f64)> {
+        let size = self.alphabet_size();
+        let even = self.is_even_length();
+        let mostly_letters = self.letters * 10 >= self.length * 9;
+        let ioc = self.index_of_coincidence;
+        // how close the index of coincidence is to English (1) or to
+        // random letters (0)
+        let englishness = ((ioc - 0.038) / (0.066 - 0.038)).clamp(0.0, 1.0);
+        let english_frequencies =
+            (1.0 - self.chi_squared / (self.letters as f64 + 50.0)).clamp(0.0, 1.0);
+        let best_period = self.periods.first().map_or(0.0, |p| p.1);
+        let periodic = ((best_period - 0.045) / (0.066 - 0.045)).clamp(0.0, 1.0);
+
+        let mut v = vec![
+            (
+                CipherFamily::Baconian,
+                match size {
+                    2 if self.length.is_multiple_of(5) => 1.0,
+                    2 => 0.8,
+                    _ => 0.0,
+                },
+            ),
+            (
+                CipherFamily::Polybius,
+                match size {
+                    3..=6 if even => 0.95,
+                    3..=6 => 0.5,
+                    7..=10 if even && !mostly_letters => 0.6,
+                    _ => 0.0,
+                },
+            ),
+            (
+                CipherFamily::Transposition,
+                if mostly_letters && size > 10 {
+                    englishness * english_frequencies
+                } else {
+                    0.0
+                },
+            ),
+            (
+                CipherFamily::Monoalphabetic,
+                if mostly_letters && size > 10 {
+                    englishness * (1.0 - english_frequencies).max(0.2)
+                } else {
+                    0.0
+                },
+            ),
+            (
+                CipherFamily::Polyalphabetic,
+                if mostly_letters && size > 10 {
+                    (1.0 - englishness) * (0.5 + 0.5 * periodic)
+                } else {
+                    0.0
+                },
+            ),
+            (
+                CipherFamily::Playfair,
+                if mostly_letters && size > 10 && size <= 25 && even && self.doubled_pairs == 0 {
+                    let middle = 1.0 - (2.0 * englishness - 1.2).abs();
+                    (0.5 + 0.5 * middle.clamp(0.0, 1.0)) * if size == 25 { 1.0 } else { 0.8 }
+                } else {
+                    0.0
+                },
+            ),
+        ];
+        v.sort_by(|a, b| b.1.total_cmp(&a.1));
+        v
+    }
+}
+
+/// Computes statistics of a ciphertext, and guesses what type of cipher
+/// produced it.
+/// ```
+/// use puzzletools::cipher::analysis::{analyze, CipherFamily};
+/// use puzzletools::cipher::Substitution;
+/// use puzzletools::cipher::vigenere::Tableau;
+/// let plaintext = "It was the best of times, it was the worst of times, it was the age of \
+///     wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch \
+///     of incredulity, it was the season of Light, it was the season of Darkness, it was \
+///     the spring of hope, it was the winter of despair, we had everything before us, we \
+///     had nothing before us, we were all going direct to Heaven, we were all going direct \
+///     the other way.";
+/// let best = |s: &str| analyze(s).guesses[0].0;
+/// assert_eq!(best(plaintext), CipherFamily::Transposition);
+/// let substitution = Substitution::from_keyword("DICKENS").encrypt(plaintext);
+/// assert_eq!(best(&substitution), CipherFamily::Monoalphabetic);
+/// let vigenere = Tableau::Vigenere.encrypt(plaintext, "DICKENS");
+/// assert_eq!(best(&vigenere), CipherFamily::Polyalphabetic);
+/// assert_eq!(analyze(&vigenere).periods[0].0 % 7, 0);
+/// assert_eq!(best("24 15 33 15 24 34 44 15 54 44 33 53 43 15 15"), CipherFamily::Polybius);
+/// ```
+///
+/// The statistics of an empty ciphertext are all zero, rather than NaN:
+/// ```
+/// use puzzletools::cipher::analysis::analyze;
+/// let a = analyze("");
+/// assert_eq!(a.index_of_coincidence, 0.0);
+/// assert_eq!(a.chi_squared, 0.0);
+/// assert!(a.guesses.iter().all(|g| g.1 == 0.0));
+/// ```
+pub fn analyze<S: Text>(s: S) -> Analysis {
+    let symbols: Vec<u8> = s
+        .bytes()
+        .filter(u8::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let letters: Vec<u8> = symbols
+        .iter()
+        .copied()
+        .filter(u8::is_ascii_alphabetic)
+        .collect();
+
+    let mut counts: HashMap<u8, usize> = HashMap::new();
+    for &c in &symbols {
+        *counts.entry(c).or_default() += 1;
+    }
+    let mut symbol_counts: Vec<(char, usize)> =
+        counts.iter().map(|(&c, &n)| (c as char, n)).collect();
+    symbol_counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let missing_letters = (b'A'..=b'Z')
+        .filter(|c| !counts.contains_key(c))
+        .map(char::from)
+        .collect();
+
+    let mut letter_counts = [0f64; 26];
+    for &c in &letters {
+        letter_counts[lett_to_num_0(c)] += 1.0;
+    }
+
+    let mut bigrams: HashMap<&[u8], usize> = HashMap::new();
+    for b in symbols.windows(2) {
+        *bigrams.entry(b).or_default() += 1;
+    }
+    let mut bigram_counts: Vec<(String, usize)> = bigrams
+        .into_iter()
+        .filter(|&(_, n)| n > 1)
+        .map(|(b, n)| (String::from_utf8(b.to_vec()).unwrap(), n))
+        .collect();
+    bigram_counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    bigram_counts.truncate(10);
+
+    let max_period = (letters.len() / 10).clamp(1, 20);
+    let mut periods = likely_periods(&letters[..], max_period);
+    periods.truncate(5);
+
+    let mut a = Analysis {
+        length: symbols.len(),
+        letters: letters.len(),
+        symbol_counts,
+        missing_letters,
+        index_of_coincidence: index_of_coincidence(&letters[..]),
+        chi_squared: chi_squared(&letter_counts, letters.len() as f64, |c| c),
+        bigram_counts,
+        doubled_symbols: symbols.windows(2).filter(|b| b[0] == b[1]).count(),
+        doubled_pairs: symbols.chunks_exact(2).filter(|b| b[0] == b[1]).count(),
+        periods,
+        guesses: Vec::new(),
+    };
+    a.guesses = a.guess();
+    a
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Length: {} symbols ({} letters), {}",
+            self.length,
+            self.letters,
+            if self.is_even_length() { "even" } else { "odd" }
+        )?;
+        writeln!(f, "Alphabet size: {}", self.alphabet_size())?;
+        if self.letters > 0 && !self.missing_letters.is_empty() {
+            writeln!(f, "Missing letters: {}", self.missing_letters)?;
+        }
+        writeln!(
+            f,
+            "Index of coincidence: {:.4} (English 0.066, random 0.038)",
+            self.index_of_coincidence
+        )?;
+        writeln!(f, "Chi-squared against English: {:.1}", self.chi_squared)?;
+        let counts: Vec<String> = self
+            .symbol_counts
+            .iter()
+            .map(|(c, n)| format!("{} {}", c, n))
+            .collect();
+        writeln!(f, "Symbol counts: {}", counts.join(", "))?;
+        let bigrams: Vec<String> = self
+            .bigram_counts
+            .iter()
+            .map(|(b, n)| format!("{} {}", b, n))
+            .collect();
+        writeln!(f, "Repeated bigrams: {}", bigrams.join(", "))?;
+        writeln!(
+            f,
+            "Doubled symbols: {} ({} within pairs)",
+            self.doubled_symbols, self.doubled_pairs
+        )?;
+        let periods: Vec<String> = self
+            .periods
+            .iter()
+            .map(|(p, ioc)| format!("{} ({:.4})", p, ioc))
+            .collect();
+        writeln!(f, "Likely periods: {}", periods.join(", "))?;
+        writeln!(f, "Likely cipher types:")?;
+        for (family, score) in self.guesses.iter().filter(|g| g.1 > 0.0) {
+            writeln!(f, "  {}: {:.2}", family, score)?;
+        }
+        Ok(())
+    }
+}
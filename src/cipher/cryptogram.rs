@@ -0,0 +1,258 @@
+//! Solving substitution cryptograms.
+
+use super::{letters, Decryption, Rng, Scorer, Substitution};
+use crate::word::{ciphergram, Text};
+use crate::wordlist::{Wordlist, WordlistEntry};
+use std::collections::HashMap;
+
+// English letters, from most to least common.
+const FREQUENCY_ORDER: &[u8; 26] = b"ETAOINSHRDLCUMWFGYPBVKJXQZ";
+
+// The number of candidate words to consider for each ciphertext word.
+const MAX_CANDIDATES: usize = 100;
+
+// The bonus (per letter) for each decrypted word that is in the wordlist.
+const WORD_BONUS: f64 = 1.0;
+
+struct Problem<'a, C> {
+    letters: Vec<u8>,
+    words: Vec<Vec<u8>>,
+    wl: &'a Wordlist,
+    scorer: &'a C,
+}
+
+impl<C: Scorer> Problem<'_, C> {
+    fn decrypt(&self, key: &[u8; 26], s: &[u8]) -> Vec<u8> {
+        s.iter().map(|&c| b'A' + key[(c - b'A') as usize]).collect()
+    }
+
+    fn score(&self, key: &[u8; 26]) -> f64 {
+        let mut score = self.scorer.score_letters(&self.decrypt(key, &self.letters));
+        if self.words.len() > 1 {
+            for w in &self.words {
+                if self.wl.get(self.decrypt(key, w)).is_some() {
+                    score += WORD_BONUS * w.len() as f64;
+                }
+            }
+        }
+        score
+    }
+
+    // Repeatedly swaps pairs of plaintext letters as long as doing so
+    // improves the score.
+    fn hill_climb(&self, key: &mut [u8; 26]) -> f64 {
+        let mut best = self.score(key);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..26 {
+                for j in i + 1..26 {
+                    key.swap(i, j);
+                    let score = self.score(key);
+                    if score > best {
+                        best = score;
+                        improved = true;
+                    } else {
+                        key.swap(i, j);
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+// A partial key, mapping ciphertext letters to plaintext letters.
+#[derive(Clone)]
+struct PartialKey {
+    plain: [Option<u8>; 26],
+    used: [bool; 26],
+}
+
+impl PartialKey {
+    fn new() -> Self {
+        Self {
+            plain: [None; 26],
+            used: [false; 26],
+        }
+    }
+
+    fn is_consistent(&self, cipher: &[u8], plain: &[u8]) -> bool {
+        cipher.iter().zip(plain).all(|(&c, &p)| {
+            let (c, p) = ((c - b'A') as usize, (p - b'A') as usize);
+            match self.plain[c] {
+                Some(q) => q as usize == p,
+                None => !self.used[p],
+            }
+        })
+    }
+
+    fn assign(&mut self, cipher: &[u8], plain: &[u8]) {
+        for (&c, &p) in cipher.iter().zip(plain) {
+            let (c, p) = ((c - b'A') as usize, p - b'A');
+            self.plain[c] = Some(p);
+            self.used[p as usize] = true;
+        }
+    }
+
+    // Fills in the rest of the key, matching the most common ciphertext
+    // letters with the most common English letters.
+    fn complete(&self, cipher_order: &[u8]) -> [u8; 26] {
+        let mut unused = FREQUENCY_ORDER
+            .iter()
+            .map(|c| c - b'A')
+            .filter(|&p| !self.used[p as usize]);
+        let mut key = [0; 26];
+        for &c in cipher_order {
+            let c = (c - b'A') as usize;
+            key[c] = self.plain[c].unwrap_or_else(|| unused.next().unwrap());
+        }
+        key
+    }
+}
+
+fn substitution_from_key(key: &[u8; 26]) -> Substitution {
+    let mut forward = [0u8; 26];
+    for (c, &p) in key.iter().enumerate() {
+        forward[p as usize] = b'A' + c as u8;
+    }
+    Substitution::from_alphabet(&forward[..]).unwrap()
+}
+
+/// Solves a substitution cryptogram, returning up to `restarts` distinct
+/// solutions, from best to worst.
+///
+/// If the ciphertext has word breaks, each ciphertext word is matched
+/// against the words in `wl` with the same pattern (see `ciphergram`) to
+/// find starting keys, and decryptions that produce words in `wl` get a
+/// bonus.  Each starting key is then improved by hill climbing on the
+/// score assigned by `scorer`, which should usually be an `NgramScorer`.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::{NgramScorer, Substitution};
+/// use puzzletools::cipher::cryptogram::solve_cryptogram;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("\
+/// THE,100\nAT,50\nAN,40\nME,30\nIN,30\nMEET,10\nSEEN,10\nHUNT,5\nHINT,5\n\
+/// PUZZLE,3\nMUZZLE,1")).unwrap();
+/// let scorer = NgramScorer::from_wordlist(&wl, 2);
+/// let key = Substitution::from_keyword("CRYPTOGRAM");
+/// let ciphertext = key.encrypt("Meet me at the puzzle hunt!");
+/// assert_eq!(ciphertext, "Fttq ft cq qat jszzet ashq!");
+/// let solutions = solve_cryptogram(&ciphertext, &wl, &scorer, 10);
+/// assert_eq!(solutions[0].plaintext, "Meet me at the puzzle hunt!");
+/// ```
+pub fn solve_cryptogram<S: Text, C: Scorer>(
+    ciphertext: S,
+    wl: &Wordlist,
+    scorer: &C,
+    restarts: usize,
+) -> Vec<Decryption<Substitution>> {
+    let text = ciphertext.as_str();
+    let problem = Problem {
+        letters: letters(text),
+        words: text
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .filter(|w| !w.is_empty())
+            .map(letters)
+            .collect(),
+        wl,
+        scorer,
+    };
+
+    let mut counts = [0usize; 26];
+    for &c in &problem.letters {
+        counts[(c - b'A') as usize] += 1;
+    }
+    let mut cipher_order: Vec<u8> = (b'A'..=b'Z').collect();
+    cipher_order.sort_by_key(|&c| std::cmp::Reverse(counts[(c - b'A') as usize]));
+
+    let candidates = if problem.words.len() > 1 {
+        word_candidates(&problem.words, wl)
+    } else {
+        Vec::new()
+    };
+
+    let mut rng = Rng::new(0);
+    let mut solutions: HashMap<[u8; 26], f64> = HashMap::new();
+    for r in 0..restarts {
+        let mut partial = PartialKey::new();
+        for (cipher, cands) in &candidates {
+            let consistent: Vec<_> = cands
+                .iter()
+                .filter(|e| partial.is_consistent(cipher, e.slug.as_bytes()))
+                .take(5)
+                .collect();
+            if !consistent.is_empty() {
+                let pick = if r == 0 {
+                    0
+                } else {
+                    rng.below(consistent.len())
+                };
+                partial.assign(cipher, consistent[pick].slug.as_bytes());
+            }
+        }
+        let mut key = partial.complete(&cipher_order);
+        if r > 0 && candidates.is_empty() {
+            for _ in 0..r.min(10) {
+                key.swap(rng.below(26), rng.below(26));
+            }
+        }
+        let score = problem.hill_climb(&mut key);
+        solutions.insert(key, score);
+    }
+
+    let mut v: Vec<_> = solutions
+        .into_iter()
+        .map(|(key, score)| {
+            let key = substitution_from_key(&key);
+            Decryption {
+                plaintext: key.decrypt(text),
+                key,
+                score,
+            }
+        })
+        .collect();
+    v.sort_by(|a, b| b.score.total_cmp(&a.score));
+    v.dedup_by(|a, b| a.plaintext == b.plaintext);
+    v
+}
+
+// Returns the distinct ciphertext words, along with the words in the
+// wordlist with the same pattern, ordered so that the most constrained
+// ciphertext words come first.
+fn word_candidates<'a>(
+    words: &[Vec<u8>],
+    wl: &'a Wordlist,
+) -> Vec<(Vec<u8>, Vec<&'a WordlistEntry>)> {
+    let mut by_pattern: HashMap<String, Vec<&WordlistEntry>> = words
+        .iter()
+        .map(|w| (ciphergram(&w[..]), Vec::new()))
+        .collect();
+    let max_len = words.iter().map(Vec::len).max().unwrap_or(0);
+    for e in wl {
+        if e.len() <= max_len
+            && e.slug.bytes().all(|c| c.is_ascii_uppercase())
+            && let Some(v) = by_pattern.get_mut(&ciphergram(&e.slug))
+        {
+            v.push(e);
+        }
+    }
+    for v in by_pattern.values_mut() {
+        v.sort_by_key(|e| std::cmp::Reverse(e.freq));
+        v.truncate(MAX_CANDIDATES);
+    }
+    let mut distinct = words.to_vec();
+    distinct.sort();
+    distinct.dedup();
+    let mut v: Vec<_> = distinct
+        .into_iter()
+        .map(|w| {
+            let cands = by_pattern[&ciphergram(&w[..])].clone();
+            (w, cands)
+        })
+        .filter(|(_, c)| !c.is_empty())
+        .collect();
+    v.sort_by_key(|(w, c)| (c.len(), std::cmp::Reverse(w.len())));
+    v
+}
@@ -0,0 +1,471 @@
+//! A simulator for the Enigma I, M3 and M4 machines.
+//!
+//! ```
+//! use puzzletools::cipher::enigma::Enigma;
+//! let e = Enigma::new("B", "I II III", "AAA", "AAA").unwrap();
+//! assert_eq!(e.encrypt("AAAAA"), "BDZGO");
+//! assert_eq!(e.decrypt("BDZGO"), "AAAAA");
+//! ```
+
+use super::{map_letters, Decryption, Scorer};
+use crate::search::Score;
+use crate::word::Text;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{self, Display};
+
+/// An Enigma rotor.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Rotor {
+    pub name: &'static str,
+    wiring: &'static [u8; 26],
+    // the positions at which the rotor causes the rotor to its left to
+    // step
+    notches: &'static [u8],
+}
+
+/// The stepping rotors I through VIII.
+pub static ROTORS: [Rotor; 8] = [
+    Rotor {
+        name: "I",
+        wiring: b"EKMFLGDQVZNTOWYHXUSPAIBRCJ",
+        notches: b"Q",
+    },
+    Rotor {
+        name: "II",
+        wiring: b"AJDKSIRUXBLHWTMCQGZNPYFVOE",
+        notches: b"E",
+    },
+    Rotor {
+        name: "III",
+        wiring: b"BDFHJLCPRTXVZNYEIWGAKMUSQO",
+        notches: b"V",
+    },
+    Rotor {
+        name: "IV",
+        wiring: b"ESOVPZJAYQUIRHXLNFTGKDCMWB",
+        notches: b"J",
+    },
+    Rotor {
+        name: "V",
+        wiring: b"VZBRGITYUPSDNHLXAWMJQOFECK",
+        notches: b"Z",
+    },
+    Rotor {
+        name: "VI",
+        wiring: b"JPGVOUMFYQBENHZRDKASXLICTW",
+        notches: b"ZM",
+    },
+    Rotor {
+        name: "VII",
+        wiring: b"NZJHGRCXMYSWBOUFAIVLPEKQDT",
+        notches: b"ZM",
+    },
+    Rotor {
+        name: "VIII",
+        wiring: b"FKQHTLXOCBJSPDZRAMEWNIUYGV",
+        notches: b"ZM",
+    },
+];
+
+/// The non-stepping fourth rotors of the M4.
+pub static GREEK_ROTORS: [Rotor; 2] = [
+    Rotor {
+        name: "Beta",
+        wiring: b"LEYJVCNIXWPBQMDRTAKZGFUHOS",
+        notches: b"",
+    },
+    Rotor {
+        name: "Gamma",
+        wiring: b"FSOKANUERHMBTIYCWLQPZXVGJD",
+        notches: b"",
+    },
+];
+
+/// An Enigma reflector.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Reflector {
+    pub name: &'static str,
+    wiring: &'static [u8; 26],
+    /// Whether this is one of the thin reflectors used with the M4.
+    pub thin: bool,
+}
+
+pub static REFLECTORS: [Reflector; 5] = [
+    Reflector {
+        name: "A",
+        wiring: b"EJMZALYXVBWFCRQUONTSPIKHGD",
+        thin: false,
+    },
+    Reflector {
+        name: "B",
+        wiring: b"YRUHQSLDPXNGOKMIEBFZCWVJAT",
+        thin: false,
+    },
+    Reflector {
+        name: "C",
+        wiring: b"FVPJIAOYEDRZXWGCTKUQSBNMHL",
+        thin: false,
+    },
+    Reflector {
+        name: "B-thin",
+        wiring: b"ENKQAUYWJICOPBLMDXZVFTHRGS",
+        thin: true,
+    },
+    Reflector {
+        name: "C-thin",
+        wiring: b"RDOBJNTKVEHMLFCWZAXGYIPSUQ",
+        thin: true,
+    },
+];
+
+// Compares names, ignoring case, spaces and punctuation.
+fn name_matches(name: &str, query: &str) -> bool {
+    let simplify = |s: &str| -> String {
+        s.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_uppercase())
+            .collect()
+    };
+    simplify(name) == simplify(query)
+}
+
+/// Looks up a rotor (I through VIII, Beta or Gamma) by name.
+pub fn rotor(name: &str) -> Option<&'static Rotor> {
+    ROTORS
+        .iter()
+        .chain(GREEK_ROTORS.iter())
+        .find(|r| name_matches(r.name, name))
+}
+
+/// Looks up a reflector (A, B, C, B-thin or C-thin) by name.
+pub fn reflector(name: &str) -> Option<&'static Reflector> {
+    REFLECTORS.iter().find(|r| name_matches(r.name, name))
+}
+
+/// A model of Enigma machine.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Model {
+    /// The Enigma I used by the German army and air force.
+    EnigmaI,
+    /// The three rotor naval Enigma.
+    M3,
+    /// The four rotor naval Enigma.
+    M4,
+}
+
+impl Model {
+    /// The stepping rotors that were available for this model.
+    pub fn rotors(self) -> &'static [Rotor] {
+        match self {
+            Model::EnigmaI => &ROTORS[..5],
+            Model::M3 | Model::M4 => &ROTORS,
+        }
+    }
+
+    /// The reflectors that were available for this model.
+    pub fn reflectors(self) -> &'static [Reflector] {
+        match self {
+            Model::EnigmaI => &REFLECTORS[..3],
+            Model::M3 => &REFLECTORS[1..3],
+            Model::M4 => &REFLECTORS[3..],
+        }
+    }
+}
+
+// Parses ring settings or positions, given either as letters ("BUL") or
+// as numbers starting from 1 ("2 21 12").
+fn parse_settings(s: &str) -> Option<Vec<u8>> {
+    if s.bytes().any(|c| c.is_ascii_digit()) {
+        s.split(|c: char| !c.is_ascii_digit())
+            .filter(|n| !n.is_empty())
+            .map(|n| match n.parse::<u8>() {
+                Ok(n @ 1..=26) => Some(n - 1),
+                _ => None,
+            })
+            .collect()
+    } else {
+        Some(
+            s.bytes()
+                .filter(u8::is_ascii_alphabetic)
+                .map(|c| c.to_ascii_uppercase() - b'A')
+                .collect(),
+        )
+    }
+}
+
+/// An Enigma machine, with its rotors set to their starting positions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Enigma {
+    reflector: &'static Reflector,
+    // from left to right
+    rotors: Vec<&'static Rotor>,
+    rings: Vec<u8>,
+    positions: Vec<u8>,
+    plugboard: [u8; 26],
+}
+
+impl Enigma {
+    /// Creates an Enigma machine with an empty plugboard.  The rotors are
+    /// listed from left to right, separated by spaces.  There should be
+    /// three rotors from I to VIII, or (for the M4) Beta or Gamma followed
+    /// by three rotors from I to VIII, in which case the reflector should
+    /// be B-thin or C-thin.  The ring settings and starting positions can
+    /// be given as letters or as numbers from 1 to 26.
+    ///
+    /// Returns `None` if the settings are not valid.
+    /// ```
+    /// use puzzletools::cipher::enigma::Enigma;
+    /// assert!(Enigma::new("B", "II IV V", "2 21 12", "BLA").is_some());
+    /// assert!(Enigma::new("C-thin", "Gamma VI VII VIII", "AAAA", "ZZZZ").is_some());
+    /// assert!(Enigma::new("B", "I I III", "AAA", "AAA").is_none());
+    /// assert!(Enigma::new("B", "Beta I II III", "AAAA", "AAAA").is_none());
+    /// ```
+    pub fn new(reflector_name: &str, rotors: &str, rings: &str, positions: &str) -> Option<Self> {
+        let reflector = reflector(reflector_name)?;
+        let rotors: Vec<&'static Rotor> = rotors
+            .split_whitespace()
+            .map(rotor)
+            .collect::<Option<_>>()?;
+        let rings = parse_settings(rings)?;
+        let positions = parse_settings(positions)?;
+        let greek = rotors.len() == 4;
+        let valid = (rotors.len() == 3 || greek)
+            && reflector.thin == greek
+            && rotors
+                .iter()
+                .enumerate()
+                .all(|(n, r)| GREEK_ROTORS.contains(r) == (greek && n == 0))
+            && (1..rotors.len()).all(|n| !rotors[..n].contains(&rotors[n]))
+            && rings.len() == rotors.len()
+            && positions.len() == rotors.len();
+        if !valid {
+            return None;
+        }
+        let mut plugboard = [0; 26];
+        for (n, p) in plugboard.iter_mut().enumerate() {
+            *p = n as u8;
+        }
+        Some(Self {
+            reflector,
+            rotors,
+            rings,
+            positions,
+            plugboard,
+        })
+    }
+
+    /// Returns a copy of this machine with the given plugboard pairs,
+    /// separated by spaces (for example, `"AV BS CG"`).  Returns `None` if
+    /// some letter is used twice.
+    pub fn with_plugboard(mut self, pairs: &str) -> Option<Self> {
+        for pair in pairs.split_whitespace() {
+            let v: Vec<u8> = pair.bytes().map(|c| c.to_ascii_uppercase()).collect();
+            if v.len() != 2 || !v.iter().all(u8::is_ascii_uppercase) || v[0] == v[1] {
+                return None;
+            }
+            let (a, b) = ((v[0] - b'A') as usize, (v[1] - b'A') as usize);
+            if self.plugboard[a] != a as u8 || self.plugboard[b] != b as u8 {
+                return None;
+            }
+            self.plugboard[a] = b as u8;
+            self.plugboard[b] = a as u8;
+        }
+        Some(self)
+    }
+
+    /// Returns a copy of this machine with the rotors set to the given
+    /// starting positions.
+    pub fn with_positions(mut self, positions: &str) -> Option<Self> {
+        let positions = parse_settings(positions)?;
+        if positions.len() != self.rotors.len() {
+            return None;
+        }
+        self.positions = positions;
+        Some(self)
+    }
+
+    /// The starting positions of the rotors, as letters.
+    pub fn positions(&self) -> String {
+        self.positions.iter().map(|&p| (b'A' + p) as char).collect()
+    }
+
+    fn step(&mut self) {
+        let n = self.positions.len();
+        let at_notch = |i: usize| self.rotors[i].notches.contains(&(b'A' + self.positions[i]));
+        let (middle_at_notch, right_at_notch) = (at_notch(n - 2), at_notch(n - 1));
+        if middle_at_notch {
+            self.positions[n - 3] = (self.positions[n - 3] + 1) % 26;
+        }
+        if middle_at_notch || right_at_notch {
+            self.positions[n - 2] = (self.positions[n - 2] + 1) % 26;
+        }
+        self.positions[n - 1] = (self.positions[n - 1] + 1) % 26;
+    }
+
+    fn encrypt_letter(&self, c: usize) -> usize {
+        let shift = |i: usize| (26 + self.positions[i] as usize - self.rings[i] as usize) % 26;
+        let mut c = self.plugboard[c] as usize;
+        for i in (0..self.rotors.len()).rev() {
+            let s = shift(i);
+            c = (self.rotors[i].wiring[(c + s) % 26] - b'A') as usize;
+            c = (c + 26 - s) % 26;
+        }
+        c = (self.reflector.wiring[c] - b'A') as usize;
+        for i in 0..self.rotors.len() {
+            let s = shift(i);
+            let wired = b'A' + ((c + s) % 26) as u8;
+            c = self.rotors[i]
+                .wiring
+                .iter()
+                .position(|&w| w == wired)
+                .unwrap();
+            c = (c + 26 - s) % 26;
+        }
+        self.plugboard[c] as usize
+    }
+
+    /// Encrypts a message, starting from the machine's starting
+    /// positions.  The rotors only step on letters; everything else is
+    /// left alone.
+    /// ```
+    /// use puzzletools::cipher::enigma::Enigma;
+    /// let e = Enigma::new("B", "II IV V", "BUL", "BLA")
+    ///     .unwrap()
+    ///     .with_plugboard("AV BS CG DL FU HZ IN KM OW RX")
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     e.encrypt("EDPUD NRGYS ZRCXN UYTPO MRMBO FKTBZ REZKM LXLVE FGUEY SIOZV EQMIK UBPMM YLKLT TDEIS"),
+    ///     "AUFKL XABTE ILUNG XVONX KURTI NOWAX KURTI NOWAX NORDW ESTLX SEBEZ XSEBE ZXUAF FLIEG"
+    /// );
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let mut machine = self.clone();
+        map_letters(s, |c| {
+            machine.step();
+            machine.encrypt_letter(c)
+        })
+    }
+
+    /// Decrypts a message.  Since the Enigma is reciprocal, this is the
+    /// same as encryption.
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        self.encrypt(s)
+    }
+}
+
+impl Display for Enigma {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reflector.name)?;
+        for r in &self.rotors {
+            write!(f, " {}", r.name)?;
+        }
+        let letters = |v: &[u8]| -> String { v.iter().map(|&p| (b'A' + p) as char).collect() };
+        write!(
+            f,
+            ", rings {}, positions {}",
+            letters(&self.rings),
+            letters(&self.positions)
+        )?;
+        let pairs: Vec<String> = (0..26)
+            .filter(|&c| (self.plugboard[c] as usize) > c)
+            .map(|c| {
+                format!(
+                    "{}{}",
+                    (b'A' + c as u8) as char,
+                    (b'A' + self.plugboard[c]) as char
+                )
+            })
+            .collect();
+        if !pairs.is_empty() {
+            write!(f, ", plugboard {}", pairs.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Searches for the settings of an Enigma machine by trying every
+/// ordering of three of the given rotors (for example,
+/// `Model::M3.rotors()`) and every starting position, and returns the
+/// `limit` best decryptions.  The reflector, ring settings and plugboard
+/// (and, for an M4, the fourth rotor and its position) are taken from
+/// `template`.
+///
+/// With the five rotors of the Enigma I, there are about a million
+/// settings to try, so this should be run in release mode.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::enigma::{search, Enigma, ROTORS};
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("ATTACK,5\nAT,10\nDAWN,3")).unwrap();
+/// let e = Enigma::new("B", "III I II", "AAA", "QEV").unwrap();
+/// let ciphertext = e.encrypt("ATTACKATDAWN");
+/// let template = Enigma::new("B", "I II III", "AAA", "AAA").unwrap();
+/// let results = search(&ciphertext, &template, &ROTORS[..3], &wl, 1);
+/// assert_eq!(results[0].key, e);
+/// assert_eq!(results[0].plaintext, "ATTACKATDAWN");
+/// ```
+pub fn search<S: Text, C: Scorer>(
+    s: S,
+    template: &Enigma,
+    rotors: &'static [Rotor],
+    scorer: &C,
+    limit: usize,
+) -> Vec<Decryption<Enigma>> {
+    let n = template.rotors.len();
+    let mut machine = template.clone();
+    // the `limit` best settings so far, with the worst on top
+    let mut best = BinaryHeap::new();
+    for (i, left) in rotors.iter().enumerate() {
+        for (j, middle) in rotors.iter().enumerate() {
+            for (k, right) in rotors.iter().enumerate() {
+                if i == j || j == k || i == k {
+                    continue;
+                }
+                machine.rotors[n - 3..].copy_from_slice(&[left, middle, right]);
+                for p in 0..26 * 26 * 26 {
+                    let positions = [(p / 676) as u8, (p / 26 % 26) as u8, (p % 26) as u8];
+                    machine.positions[n - 3..].copy_from_slice(&positions);
+                    let score = Score(scorer.score(machine.decrypt(s.as_str())));
+                    best.push(Reverse((score, [i, j, k], positions)));
+                    if best.len() > limit {
+                        best.pop();
+                    }
+                }
+            }
+        }
+    }
+    best.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((Score(score), order, positions))| {
+            let mut key = template.clone();
+            for (m, &r) in order.iter().enumerate() {
+                key.rotors[n - 3 + m] = &rotors[r];
+            }
+            key.positions[n - 3..].copy_from_slice(&positions);
+            Decryption {
+                plaintext: key.decrypt(s.as_str()),
+                key,
+                score,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn enigma_test() {
+    // double stepping of the middle rotor
+    let mut e = Enigma::new("B", "I II III", "AAA", "ADU").unwrap();
+    let mut positions = Vec::new();
+    for _ in 0..3 {
+        e.step();
+        positions.push(e.positions());
+    }
+    assert_eq!(positions, vec!["ADV", "AEW", "BFX"]);
+    // an M4 with the Beta rotor at A and the thin B reflector acts like
+    // an M3 with the B reflector
+    let text = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG";
+    let m3 = Enigma::new("B", "II IV V", "BUL", "BLA").unwrap();
+    let m4 = Enigma::new("B-thin", "Beta II IV V", "ABUL", "ABLA").unwrap();
+    assert_eq!(m3.encrypt(text), m4.encrypt(text));
+}
@@ -0,0 +1,278 @@
+//! Polybius squares, tap code, and ciphers built on them (Nihilist,
+//! ADFGX and ADFGVX).
+
+use super::square::{Square, ALPHABET_36};
+use super::transposition::Columnar;
+use crate::word::Text;
+
+/// A Polybius square, which encodes each symbol as its row and column
+/// labels.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Polybius {
+    pub square: Square,
+    labels: Vec<u8>,
+}
+
+impl Polybius {
+    /// Creates a Polybius square whose rows and columns are labeled with
+    /// the digits starting from 1.
+    /// ```
+    /// use puzzletools::cipher::polybius::Polybius;
+    /// use puzzletools::cipher::square::Square;
+    /// let p = Polybius::new(Square::keyed_5x5(""));
+    /// assert_eq!(p.encrypt("Jinx"), "24 24 33 53");
+    /// assert_eq!(p.decrypt("2424 3353"), "IINX");
+    /// ```
+    pub fn new(square: Square) -> Self {
+        let labels = (b'1'..).take(square.size()).collect();
+        Self { square, labels }
+    }
+
+    /// Creates a Polybius square with the given row and column labels.
+    /// Returns `None` if the number of labels is not the size of the
+    /// square, or if some label is repeated.
+    /// ```
+    /// use puzzletools::cipher::polybius::Polybius;
+    /// use puzzletools::cipher::square::Square;
+    /// let p = Polybius::with_labels(Square::keyed_5x5(""), "ADFGX").unwrap();
+    /// assert_eq!(p.encrypt("Hi"), "DF DG");
+    /// assert!(Polybius::with_labels(Square::keyed_5x5(""), "ADFG").is_none());
+    /// ```
+    pub fn with_labels(square: Square, labels: &str) -> Option<Self> {
+        let labels: Vec<u8> = labels.bytes().map(|c| c.to_ascii_uppercase()).collect();
+        let mut sorted = labels.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if labels.len() != square.size() || sorted.len() != labels.len() {
+            return None;
+        }
+        Some(Self { square, labels })
+    }
+
+    fn pairs<S: Text>(&self, s: S) -> Vec<[u8; 2]> {
+        self.square
+            .filter(s)
+            .into_iter()
+            .map(|c| {
+                let (r, c) = self.square.position(c).unwrap();
+                [self.labels[r], self.labels[c]]
+            })
+            .collect()
+    }
+
+    /// Encodes each symbol of `s` that is in the square as its row label
+    /// followed by its column label.  The pairs are separated by spaces.
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let pairs = self.pairs(s);
+        let v: Vec<&str> = pairs
+            .iter()
+            .map(|p| std::str::from_utf8(p).unwrap())
+            .collect();
+        v.join(" ")
+    }
+
+    fn label(&self, c: u8) -> Option<usize> {
+        let c = c.to_ascii_uppercase();
+        self.labels.iter().position(|&l| l == c)
+    }
+
+    /// Decodes a stream of labels, taken two at a time.  Characters that
+    /// are not labels are ignored.
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let v: Vec<usize> = s.bytes().filter_map(|c| self.label(c)).collect();
+        self.decrypt_coordinates(v.chunks_exact(2).map(|p| (p[0], p[1])))
+    }
+
+    /// Decodes a sequence of knocks, where each knock is written as a
+    /// symbol and knocks are separated into groups by spaces (or other
+    /// whitespace).  The sizes of the groups are taken two at a time as
+    /// the row and column numbers.
+    /// ```
+    /// use puzzletools::cipher::polybius::tap_code;
+    /// assert_eq!(tap_code().decrypt_knocks(".. ... . ..... ... . ... . ... ...."), "HELLO");
+    /// assert_eq!(tap_code().decrypt_knocks("xx xxx x xxxxx"), "HE");
+    /// ```
+    pub fn decrypt_knocks<S: Text>(&self, s: S) -> String {
+        let counts: Vec<usize> = s.as_str().split_whitespace().map(|g| g.len()).collect();
+        self.decrypt_coordinates(
+            counts
+                .chunks_exact(2)
+                .filter(|p| p[0] > 0 && p[1] > 0)
+                .map(|p| (p[0] - 1, p[1] - 1)),
+        )
+    }
+
+    /// Decodes a sequence of (row, column) pairs, numbered from 0.  Pairs
+    /// that are outside the square are skipped.
+    pub fn decrypt_coordinates<I: IntoIterator<Item = (usize, usize)>>(&self, it: I) -> String {
+        it.into_iter()
+            .filter_map(|(r, c)| self.square.get(r, c))
+            .collect()
+    }
+
+    /// Encodes each symbol of `s` as a sequence of knocks (see
+    /// `decrypt_knocks`), using `.` for a knock.
+    /// ```
+    /// use puzzletools::cipher::polybius::tap_code;
+    /// assert_eq!(tap_code().encrypt_knocks("Hi"), ".. ... .. ....");
+    /// ```
+    pub fn encrypt_knocks<S: Text>(&self, s: S) -> String {
+        let groups: Vec<String> = self
+            .square
+            .filter(s)
+            .into_iter()
+            .flat_map(|c| {
+                let (r, c) = self.square.position(c).unwrap();
+                [".".repeat(r + 1), ".".repeat(c + 1)]
+            })
+            .collect();
+        groups.join(" ")
+    }
+}
+
+/// Returns the square used for tap code, in which K is merged into C.
+/// ```
+/// use puzzletools::cipher::polybius::tap_code;
+/// assert_eq!(tap_code().encrypt("Knock"), "13 33 34 13 13");
+/// ```
+pub fn tap_code() -> Polybius {
+    let square = Square::new("ABCDEFGHIJLMNOPQRSTUVWXYZ")
+        .unwrap()
+        .with_merge('K', 'C');
+    Polybius::new(square)
+}
+
+/// The Nihilist cipher.  Each letter is replaced by its two digit
+/// Polybius number, to which the number of the corresponding letter of
+/// the (repeating) key is added.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Nihilist {
+    pub polybius: Polybius,
+    key: Vec<u32>,
+}
+
+impl Nihilist {
+    /// Creates a Nihilist cipher using a 5x5 square built from
+    /// `square_keyword` (with J merged into I).
+    pub fn new<K: Text, L: Text>(square_keyword: K, key: L) -> Self {
+        let polybius = Polybius::new(Square::keyed_5x5(square_keyword));
+        let key = polybius_numbers(&polybius, key);
+        Self { polybius, key }
+    }
+
+    /// Encrypts a message.  The numbers are separated by spaces.
+    /// ```
+    /// use puzzletools::cipher::polybius::Nihilist;
+    /// let n = Nihilist::new("ZEBRAS", "RUSSIAN");
+    /// assert_eq!(
+    ///     n.encrypt("Dynamite Winter Palace"),
+    ///     "37 106 62 36 67 47 86 26 104 53 62 77 27 55 57 66 55 36 54 27"
+    /// );
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let v: Vec<String> = polybius_numbers(&self.polybius, s)
+            .into_iter()
+            .zip(self.key.iter().cycle())
+            .map(|(n, k)| (n + k).to_string())
+            .collect();
+        v.join(" ")
+    }
+
+    /// Decrypts a message, given as numbers separated by anything other
+    /// than digits.  Numbers that do not decrypt to a cell of the square
+    /// are skipped.
+    /// ```
+    /// use puzzletools::cipher::polybius::Nihilist;
+    /// let n = Nihilist::new("ZEBRAS", "RUSSIAN");
+    /// assert_eq!(n.decrypt("37 106 62 36 67 47 86 26"), "DYNAMITE");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        if self.key.is_empty() {
+            return String::new();
+        }
+        let numbers = s
+            .as_str()
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|n| n.parse::<u32>().ok());
+        self.polybius.decrypt_coordinates(
+            numbers
+                .zip(self.key.iter().cycle())
+                .filter_map(|(n, k)| n.checked_sub(*k))
+                .filter(|&n| n % 10 > 0 && n / 10 > 0)
+                .map(|n| (n as usize / 10 - 1, n as usize % 10 - 1)),
+        )
+    }
+}
+
+// The two digit numbers of the symbols of `s`.
+fn polybius_numbers<S: Text>(polybius: &Polybius, s: S) -> Vec<u32> {
+    polybius
+        .pairs(s)
+        .iter()
+        .map(|p| ((p[0] - b'0') * 10 + p[1] - b'0') as u32)
+        .collect()
+}
+
+/// The ADFGX and ADFGVX ciphers, which encode each symbol with a
+/// Polybius square labeled ADFGX (for a 5x5 square) or ADFGVX (for a 6x6
+/// square), and then apply a columnar transposition.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Adfgvx {
+    pub polybius: Polybius,
+    pub transposition: Columnar,
+}
+
+impl Adfgvx {
+    /// Creates an ADFGX or ADFGVX cipher, depending on whether the square
+    /// is 5x5 or 6x6.  Returns `None` for squares of other sizes.
+    pub fn new<K: Text>(square: Square, keyword: K) -> Option<Self> {
+        let labels = match square.size() {
+            5 => "ADFGX",
+            6 => "ADFGVX",
+            _ => return None,
+        };
+        Some(Self {
+            polybius: Polybius::with_labels(square, labels)?,
+            transposition: Columnar::from_keyword(keyword),
+        })
+    }
+
+    /// Creates an ADFGX cipher with a 5x5 square built from
+    /// `square_keyword` (with J merged into I).
+    pub fn keyed_5x5<K: Text, L: Text>(square_keyword: K, keyword: L) -> Self {
+        Self::new(Square::keyed_5x5(square_keyword), keyword).unwrap()
+    }
+
+    /// Creates an ADFGVX cipher with a 6x6 square of letters and digits
+    /// built from `square_keyword`.
+    pub fn keyed_6x6<K: Text, L: Text>(square_keyword: K, keyword: L) -> Self {
+        let square = Square::from_keyword(square_keyword, ALPHABET_36).unwrap();
+        Self::new(square, keyword).unwrap()
+    }
+
+    /// ```
+    /// use puzzletools::cipher::polybius::Adfgvx;
+    /// use puzzletools::cipher::square::Square;
+    /// let square = Square::new("NA1C3H8TB2OME5WRPD4F6G7I9J0KLQSUVXYZ").unwrap();
+    /// let a = Adfgvx::new(square, "PRIVACY").unwrap();
+    /// assert_eq!(a.encrypt("Attack at 1200AM"), "DGDDDAGDDGAFADDFDADVDVFAADVX");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let labels: String = self
+            .polybius
+            .pairs(s)
+            .iter()
+            .flat_map(|p| p.iter().map(|&c| c as char))
+            .collect();
+        self.transposition.encrypt(labels)
+    }
+
+    /// ```
+    /// use puzzletools::cipher::polybius::Adfgvx;
+    /// let a = Adfgvx::keyed_5x5("ZEBRAS", "CARGO");
+    /// assert_eq!(a.decrypt(a.encrypt("Attack at dawn")), "ATTACKATDAWN");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        self.polybius.decrypt(self.transposition.decrypt(s))
+    }
+}
@@ -0,0 +1,550 @@
+//! Ciphers that encrypt several letters at a time: Playfair, two-square,
+//! four-square, Bifid and Trifid.
+//!
+//! These ciphers discard everything other than letters (and merge
+//! letters that are not in the square), so their output is uppercase
+//! with no spaces.
+//!
+//! To try every word in a wordlist as the keyword, use `try_keys`:
+//! ```
+//! use std::io::Cursor;
+//! use puzzletools::cipher::try_keys;
+//! use puzzletools::cipher::polygraphic::Playfair;
+//! use puzzletools::wordlist::Wordlist;
+//! let wl = Wordlist::load_from_reader(Cursor::new("HIDE,5\nTHE,10\nGOLD,3\nEXAMPLE,1")).unwrap();
+//! let ciphertext = Playfair::new("EXAMPLE").encrypt("Hide the gold");
+//! let keys = wl.iter().map(|w| &w.slug);
+//! let best = try_keys(&ciphertext, keys, |k, s| Playfair::new(k).decrypt(s), &wl).next().unwrap();
+//! assert_eq!(best.key, "EXAMPLE");
+//! assert_eq!(best.plaintext, "HIDETHEGOLDX");
+//! ```
+
+use super::keyword_alphabet;
+use super::square::Square;
+use crate::letter::Letter;
+use crate::word::Text;
+
+// The letter added to the end of a message with an odd number of
+// letters by the two-square and four-square ciphers.
+const PADDING: u8 = b'X';
+
+/// The Playfair cipher.
+#[derive(Clone, Debug)]
+pub struct Playfair {
+    square: Square,
+    // The letter inserted between doubled letters, and at the end of a
+    // message with an odd number of letters.
+    filler: u8,
+    // The filler used when the doubled letter is `filler` itself.
+    alternate_filler: u8,
+}
+
+impl Playfair {
+    /// Creates a Playfair cipher with a 5x5 square built from the keyword
+    /// (with J merged into I), using X as the filler (and Q as the filler
+    /// for a doubled X).
+    pub fn new<K: Text>(keyword: K) -> Self {
+        Self::with_square(Square::keyed_5x5(keyword)).unwrap()
+    }
+
+    /// Creates a Playfair cipher with the given square, using X as the
+    /// filler and Q as the alternate filler.  To merge letters other than
+    /// I and J, construct the square with `Square::from_keyword` and
+    /// `Square::with_merge`.  Returns `None` if X or Q is not in the
+    /// square; use `with_fillers` to change them.
+    /// ```
+    /// use puzzletools::cipher::polygraphic::Playfair;
+    /// use puzzletools::cipher::square::Square;
+    /// let no_x = Square::new("ABCDEFGHIKLMNOPQRSTUVWYZ0").unwrap();
+    /// assert!(Playfair::with_square(no_x).is_none());
+    /// ```
+    pub fn with_square(square: Square) -> Option<Self> {
+        Self {
+            square,
+            filler: b'X',
+            alternate_filler: b'Q',
+        }
+        .with_fillers('X', 'Q')
+    }
+
+    /// Changes the fillers.  Returns `None` if either filler is not in
+    /// the square, or if they are the same.
+    /// ```
+    /// use puzzletools::cipher::polygraphic::Playfair;
+    /// let p = Playfair::new("").with_fillers('Z', 'X').unwrap();
+    /// assert_eq!(p.digraphs("balloons"), "BALZLOONSZ");
+    /// assert!(Playfair::new("").with_fillers('0', 'X').is_none());
+    /// ```
+    pub fn with_fillers(self, filler: char, alternate_filler: char) -> Option<Self> {
+        let filler = self.square.normalize(filler.byte())?;
+        let alternate_filler = self.square.normalize(alternate_filler.byte())?;
+        if filler == alternate_filler {
+            return None;
+        }
+        Some(Self {
+            filler,
+            alternate_filler,
+            ..self
+        })
+    }
+
+    /// The key square.
+    pub fn square(&self) -> &Square {
+        &self.square
+    }
+
+    /// Splits the letters of a plaintext into pairs, inserting fillers as
+    /// needed.
+    /// ```
+    /// use puzzletools::cipher::polygraphic::Playfair;
+    /// let p = Playfair::new("");
+    /// assert_eq!(p.digraphs("Hello, Jim"), "HELXLOIXIM");
+    /// assert_eq!(p.digraphs("taxx"), "TAXQXQ");
+    /// ```
+    pub fn digraphs<S: Text>(&self, s: S) -> String {
+        let letters = self.square.filter(s);
+        let mut v = Vec::with_capacity(letters.len() + 2);
+        for c in letters {
+            if v.len() % 2 == 1 && v.last() == Some(&c) {
+                v.push(self.filler_for(c));
+            }
+            v.push(c);
+        }
+        if v.len() % 2 == 1 {
+            let last = *v.last().unwrap();
+            v.push(self.filler_for(last));
+        }
+        v.into_iter().map(char::from).collect()
+    }
+
+    fn filler_for(&self, c: u8) -> u8 {
+        if c == self.filler {
+            self.alternate_filler
+        } else {
+            self.filler
+        }
+    }
+
+    // The letters of `digraphs` must all be in the square (as they are
+    // after `Square::filter`, since the fillers are checked when the
+    // cipher is built).
+    fn transform(&self, digraphs: &[u8], shift: usize) -> String {
+        let size = self.square.size();
+        let mut v = String::with_capacity(digraphs.len());
+        for pair in digraphs.chunks_exact(2) {
+            let (r1, c1) = self.square.position(pair[0]).expect("letter not in square");
+            let (r2, c2) = self.square.position(pair[1]).expect("letter not in square");
+            let (a, b) = if r1 == r2 {
+                (
+                    self.square.at(r1, (c1 + shift) % size),
+                    self.square.at(r2, (c2 + shift) % size),
+                )
+            } else if c1 == c2 {
+                (
+                    self.square.at((r1 + shift) % size, c1),
+                    self.square.at((r2 + shift) % size, c2),
+                )
+            } else {
+                (self.square.at(r1, c2), self.square.at(r2, c1))
+            };
+            v.push(a as char);
+            v.push(b as char);
+        }
+        v
+    }
+
+    /// ```
+    /// use puzzletools::cipher::polygraphic::Playfair;
+    /// let p = Playfair::new("playfair example");
+    /// assert_eq!(p.encrypt("Hide the gold in the tree stump"), "BMODZBXDNABEKUDMUIXMMOUVIF");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        self.transform(self.digraphs(s).as_bytes(), 1)
+    }
+
+    /// Decrypts a message.  Fillers are not removed.  If the ciphertext
+    /// has an odd number of letters, the last letter is ignored.
+    /// ```
+    /// use puzzletools::cipher::polygraphic::Playfair;
+    /// let p = Playfair::new("playfair example");
+    /// assert_eq!(p.decrypt("BMODZBXDNABEKUDMUIXMMOUVIF"), "HIDETHEGOLDINTHETREXESTUMP");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let size = self.square.size();
+        self.transform(&self.square.filter(s), size - 1)
+    }
+}
+
+/// The two-square cipher, with the squares stacked vertically.  The
+/// first letter of each pair is found in the upper square and the second
+/// letter in the lower square.
+#[derive(Clone, Debug)]
+pub struct TwoSquare {
+    upper: Square,
+    lower: Square,
+}
+
+impl TwoSquare {
+    /// Creates a two-square cipher from two keywords, using 5x5 squares
+    /// with J merged into I.
+    pub fn new<K: Text, L: Text>(upper: K, lower: L) -> Self {
+        Self::with_squares(Square::keyed_5x5(upper), Square::keyed_5x5(lower)).unwrap()
+    }
+
+    /// Creates a two-square cipher from two squares.  Returns `None` if
+    /// the squares are different sizes, or if X (which is used as
+    /// padding) is not in the lower square.
+    pub fn with_squares(upper: Square, lower: Square) -> Option<Self> {
+        if upper.size() != lower.size() || lower.normalize(PADDING).is_none() {
+            return None;
+        }
+        Some(Self { upper, lower })
+    }
+
+    // The letters of each pair must be in the upper and lower squares
+    // respectively, as they are after `alternating`.
+    fn transform(&self, s: &[u8]) -> String {
+        let mut v = String::with_capacity(s.len());
+        for pair in s.chunks_exact(2) {
+            let (r1, c1) = self.upper.position(pair[0]).expect("letter not in square");
+            let (r2, c2) = self.lower.position(pair[1]).expect("letter not in square");
+            v.push(self.upper.at(r1, c2) as char);
+            v.push(self.lower.at(r2, c1) as char);
+        }
+        v
+    }
+
+    /// Encrypts a message, adding an X at the end if it has an odd number
+    /// of letters.  The first letter of each pair is skipped if it is not
+    /// in the upper square, and the second if it is not in the lower
+    /// square.  Pairs whose letters are in the same column are left
+    /// unchanged.
+    /// This example uses squares with Q omitted:
+    /// ```
+    /// use puzzletools::cipher::polygraphic::TwoSquare;
+    /// use puzzletools::cipher::square::Square;
+    /// let alphabet = "ABCDEFGHIJKLMNOPRSTUVWXYZ";
+    /// let t = TwoSquare::with_squares(
+    ///     Square::from_keyword("EXAMPLE", alphabet).unwrap(),
+    ///     Square::from_keyword("KEYWORD", alphabet).unwrap(),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(t.encrypt("Help me Obi Wan Kenobi"), "HEDLXWSDJYANHOTKDG");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let mut letters = alternating(s, &self.upper, &self.lower);
+        if letters.len() % 2 == 1 {
+            letters.push(PADDING);
+        }
+        self.transform(&letters)
+    }
+
+    /// ```
+    /// use puzzletools::cipher::polygraphic::TwoSquare;
+    /// let t = TwoSquare::new("EXAMPLE", "KEYWORD");
+    /// assert_eq!(t.encrypt("Help me Obi Wan Kenobi"), "HECMXWSRKYXPHWNODG");
+    /// assert_eq!(t.decrypt("HECMXWSRKYXPHWNODG"), "HELPMEOBIWANKENOBI");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        // the two-square cipher is its own inverse
+        self.encrypt(s)
+    }
+}
+
+/// The four-square cipher.  The plaintext squares (upper left and lower
+/// right) are unkeyed, and the ciphertext squares (upper right and lower
+/// left) are keyed.
+#[derive(Clone, Debug)]
+pub struct FourSquare {
+    plain: Square,
+    upper_right: Square,
+    lower_left: Square,
+}
+
+impl FourSquare {
+    /// Creates a four-square cipher from two keywords, using 5x5 squares
+    /// with J merged into I.
+    pub fn new<K: Text, L: Text>(upper_right: K, lower_left: L) -> Self {
+        Self::with_squares(
+            Square::keyed_5x5(""),
+            Square::keyed_5x5(upper_right),
+            Square::keyed_5x5(lower_left),
+        )
+        .unwrap()
+    }
+
+    /// Creates a four-square cipher from its plaintext square (used for
+    /// both the upper left and the lower right) and its ciphertext
+    /// squares.  Returns `None` if the squares are different sizes, or if
+    /// X (which is used as padding) is not in the plaintext square.
+    pub fn with_squares(plain: Square, upper_right: Square, lower_left: Square) -> Option<Self> {
+        if upper_right.size() != plain.size()
+            || lower_left.size() != plain.size()
+            || plain.normalize(PADDING).is_none()
+        {
+            return None;
+        }
+        Some(Self {
+            plain,
+            upper_right,
+            lower_left,
+        })
+    }
+
+    /// Encrypts a message, adding an X at the end if it has an odd number
+    /// of letters.  This example uses squares with Q omitted:
+    /// ```
+    /// use puzzletools::cipher::polygraphic::FourSquare;
+    /// use puzzletools::cipher::square::Square;
+    /// let alphabet = "ABCDEFGHIJKLMNOPRSTUVWXYZ";
+    /// let f = FourSquare::with_squares(
+    ///     Square::new(alphabet).unwrap(),
+    ///     Square::from_keyword("EXAMPLE", alphabet).unwrap(),
+    ///     Square::from_keyword("KEYWORD", alphabet).unwrap(),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(f.encrypt("Help me Obi Wan Kenobi"), "FYGMKYHOBXMFKKKIMD");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let mut letters = self.plain.filter(s);
+        if letters.len() % 2 == 1 {
+            letters.push(PADDING);
+        }
+        let mut v = String::with_capacity(letters.len());
+        for pair in letters.chunks_exact(2) {
+            let (r1, c1) = self.plain.position(pair[0]).expect("letter not in square");
+            let (r2, c2) = self.plain.position(pair[1]).expect("letter not in square");
+            v.push(self.upper_right.at(r1, c2) as char);
+            v.push(self.lower_left.at(r2, c1) as char);
+        }
+        v
+    }
+
+    /// Decrypts a message.  The first letter of each pair is skipped if it
+    /// is not in the upper right square, and the second if it is not in
+    /// the lower left square.  If there are an odd number of letters, the
+    /// last letter is ignored.
+    /// ```
+    /// use puzzletools::cipher::polygraphic::FourSquare;
+    /// let f = FourSquare::new("EXAMPLE", "KEYWORD");
+    /// assert_eq!(f.encrypt("Help me Obi Wan Kenobi"), "FYNFNEHWBXAFFOKHMD");
+    /// assert_eq!(f.decrypt("FYNFNEHWBXAFFOKHMD"), "HELPMEOBIWANKENOBI");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let letters = alternating(s, &self.upper_right, &self.lower_left);
+        let mut v = String::with_capacity(letters.len());
+        for pair in letters.chunks_exact(2) {
+            let (r1, c2) = self
+                .upper_right
+                .position(pair[0])
+                .expect("letter not in square");
+            let (r2, c1) = self
+                .lower_left
+                .position(pair[1])
+                .expect("letter not in square");
+            v.push(self.plain.at(r1, c1) as char);
+            v.push(self.plain.at(r2, c2) as char);
+        }
+        v
+    }
+}
+
+// Keeps the symbols of `s` that can be looked up in `first` (for the
+// first symbol of each pair) or `second` (for the second), skipping the
+// others.  The symbols are not normalized, so that each one is looked up
+// using the merges of its own square.
+fn alternating<S: Text>(s: S, first: &Square, second: &Square) -> Vec<u8> {
+    let mut v = Vec::new();
+    for c in s.bytes().map(|c| c.to_ascii_uppercase()) {
+        let square = if v.len() % 2 == 0 { first } else { second };
+        if square.normalize(c).is_some() {
+            v.push(c);
+        }
+    }
+    v
+}
+
+/// The Bifid cipher.
+#[derive(Clone, Debug)]
+pub struct Bifid {
+    pub square: Square,
+    /// The number of letters that are fractionated together, or 0 to
+    /// fractionate the whole message at once.
+    pub period: usize,
+}
+
+impl Bifid {
+    /// Creates a Bifid cipher with a 5x5 square built from the keyword
+    /// (with J merged into I).
+    pub fn new<K: Text>(keyword: K, period: usize) -> Self {
+        Self {
+            square: Square::keyed_5x5(keyword),
+            period,
+        }
+    }
+
+    fn blocks(&self, letters: &[u8]) -> Vec<Vec<u8>> {
+        let period = if self.period == 0 {
+            letters.len().max(1)
+        } else {
+            self.period
+        };
+        letters.chunks(period).map(<[u8]>::to_vec).collect()
+    }
+
+    /// ```
+    /// use puzzletools::cipher::polygraphic::Bifid;
+    /// use puzzletools::cipher::square::Square;
+    /// let b = Bifid { square: Square::new("BGWKZQPNDSIOAXEFCLUMTHYVR").unwrap(), period: 0 };
+    /// assert_eq!(b.encrypt("Flee at once"), "UAEOLWRINS");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let mut v = String::new();
+        for block in self.blocks(&self.square.filter(s)) {
+            let pos: Vec<_> = block
+                .iter()
+                .map(|&c| self.square.position(c).unwrap())
+                .collect();
+            let digits: Vec<usize> = pos
+                .iter()
+                .map(|p| p.0)
+                .chain(pos.iter().map(|p| p.1))
+                .collect();
+            for d in digits.chunks_exact(2) {
+                v.push(self.square.at(d[0], d[1]) as char);
+            }
+        }
+        v
+    }
+
+    /// ```
+    /// use puzzletools::cipher::polygraphic::Bifid;
+    /// use puzzletools::cipher::square::Square;
+    /// let b = Bifid { square: Square::new("BGWKZQPNDSIOAXEFCLUMTHYVR").unwrap(), period: 0 };
+    /// assert_eq!(b.decrypt("UAEOLWRINS"), "FLEEATONCE");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let mut v = String::new();
+        for block in self.blocks(&self.square.filter(s)) {
+            let digits: Vec<usize> = block
+                .iter()
+                .flat_map(|&c| {
+                    let (r, c) = self.square.position(c).unwrap();
+                    [r, c]
+                })
+                .collect();
+            let (rows, cols) = digits.split_at(block.len());
+            for (&r, &c) in rows.iter().zip(cols) {
+                v.push(self.square.at(r, c) as char);
+            }
+        }
+        v
+    }
+}
+
+/// The Trifid cipher, which uses a 3x3x3 cube containing the 26 letters
+/// and `+`.
+#[derive(Clone, Debug)]
+pub struct Trifid {
+    cube: Vec<u8>,
+    /// The number of letters that are fractionated together, or 0 to
+    /// fractionate the whole message at once.
+    pub period: usize,
+}
+
+impl Trifid {
+    /// Creates a Trifid cipher whose cube is filled (layer by layer, row
+    /// by row) with the keyword followed by the rest of the alphabet and
+    /// then `+`.
+    pub fn new<K: Text>(keyword: K, period: usize) -> Self {
+        let mut cube = keyword_alphabet(keyword).into_bytes();
+        cube.push(b'+');
+        Self { cube, period }
+    }
+
+    fn digits(&self, c: u8) -> Option<[usize; 3]> {
+        let c = c.to_ascii_uppercase();
+        let n = self.cube.iter().position(|&d| d == c)?;
+        Some([n / 9, (n / 3) % 3, n % 3])
+    }
+
+    fn letters<S: Text>(&self, s: S) -> Vec<[usize; 3]> {
+        s.bytes().filter_map(|c| self.digits(c)).collect()
+    }
+
+    fn period_for(&self, len: usize) -> usize {
+        if self.period == 0 {
+            len.max(1)
+        } else {
+            self.period
+        }
+    }
+
+    /// Encrypts a message.  Only letters and `+` are kept.
+    /// ```
+    /// use puzzletools::cipher::polygraphic::Trifid;
+    /// let t = Trifid::new("FELIX MARIE DELASTELLE", 5);
+    /// assert_eq!(t.decrypt(t.encrypt("Aide-toi, le ciel t'aidera")), "AIDETOILECIELTAIDERA");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let letters = self.letters(s);
+        let mut v = String::with_capacity(letters.len());
+        for block in letters.chunks(self.period_for(letters.len())) {
+            let digits: Vec<usize> = (0..3)
+                .flat_map(|i| block.iter().map(move |d| d[i]))
+                .collect();
+            for d in digits.chunks_exact(3) {
+                v.push(self.cube[9 * d[0] + 3 * d[1] + d[2]] as char);
+            }
+        }
+        v
+    }
+
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let letters = self.letters(s);
+        let mut v = String::with_capacity(letters.len());
+        for block in letters.chunks(self.period_for(letters.len())) {
+            let digits: Vec<usize> = block.iter().flatten().copied().collect();
+            let n = block.len();
+            for i in 0..n {
+                let (a, b, c) = (digits[i], digits[n + i], digits[2 * n + i]);
+                v.push(self.cube[9 * a + 3 * b + c] as char);
+            }
+        }
+        v
+    }
+}
+
+#[test]
+fn mismatched_squares_test() {
+    // the upper square merges J into I, and the lower square has J but
+    // no Q
+    let no_q = Square::new("ABCDEFGHIJKLMNOPRSTUVWXYZ").unwrap();
+    let t = TwoSquare::with_squares(Square::keyed_5x5("EXAMPLE"), no_q.clone()).unwrap();
+    let c = t.encrypt("Quick jumping jacks");
+    assert_eq!(c.len(), 18);
+    // the J of JACKS is the first letter of a pair, so it becomes I
+    assert_eq!(t.decrypt(&c), "QUICKJUMPINGIACKSX");
+    let f = FourSquare::with_squares(no_q.clone(), Square::keyed_5x5("EXAMPLE"), no_q).unwrap();
+    let c = f.encrypt("Jumping jacks");
+    assert_eq!(c.len(), 12);
+    assert_eq!(f.decrypt(&c), "JUMPINGJACKS");
+    assert!(TwoSquare::with_squares(Square::keyed_5x5(""), Square::new("ABCD").unwrap()).is_none());
+}
+
+#[test]
+fn fractionation_test() {
+    let t = Trifid::new("FELIX MARIE DELASTELLE", 5);
+    assert_eq!(
+        t.encrypt("Aide-toi, le ciel t'aidera"),
+        "FMJFVOISSUFTFPUFEQQC"
+    );
+    assert_eq!(t.decrypt("FMJFVOISSUFTFPUFEQQC"), "AIDETOILECIELTAIDERA");
+    for period in 0..8 {
+        let b = Bifid::new("PLAYFAIR", period);
+        assert_eq!(
+            b.decrypt(b.encrypt("The quick brown fox")),
+            "THEQUICKBROWNFOX"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Key squares, as used by Playfair, Polybius and related ciphers.
+
+use super::letters;
+use crate::word::Text;
+
+/// The 25 letters used in a 5x5 square where J is merged with I.
+pub const ALPHABET_NO_J: &str = "ABCDEFGHIKLMNOPQRSTUVWXYZ";
+
+/// The 36 symbols used in a 6x6 square.
+pub const ALPHABET_36: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// A square grid of distinct symbols.  Symbols that are not in the grid
+/// can be merged with symbols that are (for example, J with I).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Square {
+    size: usize,
+    cells: Vec<u8>,
+    merges: Vec<(u8, u8)>,
+}
+
+impl Square {
+    /// Creates a square from its symbols, listed row by row.  Returns
+    /// `None` if the number of symbols is not a perfect square, if some
+    /// symbol is repeated, or if some symbol is not a printable ASCII
+    /// character.  Lowercase letters are converted to uppercase.
+    /// ```
+    /// use puzzletools::cipher::square::Square;
+    /// let sq = Square::new("BGWKZQPNDSIOAXEFCLUMTHYVR").unwrap();
+    /// assert_eq!(sq.size(), 5);
+    /// assert_eq!(sq.get(1, 0), Some('Q'));
+    /// assert!(Square::new("ABCDE").is_none());
+    /// ```
+    pub fn new<S: Text>(cells: S) -> Option<Self> {
+        let cells: Vec<u8> = cells.bytes().map(|c| c.to_ascii_uppercase()).collect();
+        let size = (1..=cells.len()).find(|n| n * n >= cells.len())?;
+        let mut sorted = cells.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if size * size != cells.len()
+            || sorted.len() != cells.len()
+            || !cells.iter().all(u8::is_ascii_graphic)
+        {
+            return None;
+        }
+        Some(Self {
+            size,
+            cells,
+            merges: Vec::new(),
+        })
+    }
+
+    /// Creates a square whose symbols are the keyword (with repeated
+    /// symbols removed) followed by the rest of the alphabet.  Symbols in
+    /// the keyword that are not in the alphabet are skipped.
+    /// ```
+    /// use puzzletools::cipher::square::{Square, ALPHABET_36};
+    /// let sq = Square::from_keyword("puzzle 2024", ALPHABET_36).unwrap();
+    /// assert_eq!(sq.to_string(), "PUZLE2\n04ABCD\nFGHIJK\nMNOQRS\nTVWXY1\n356789");
+    /// ```
+    pub fn from_keyword<K: Text>(keyword: K, alphabet: &str) -> Option<Self> {
+        let mut cells = Vec::with_capacity(alphabet.len());
+        let key = keyword.bytes().map(|c| c.to_ascii_uppercase());
+        for c in key.chain(alphabet.bytes()) {
+            if alphabet.as_bytes().contains(&c) && !cells.contains(&c) {
+                cells.push(c);
+            }
+        }
+        Self::new(&cells[..])
+    }
+
+    /// Creates a 5x5 square from a keyword, with J merged into I.
+    /// ```
+    /// use puzzletools::cipher::square::Square;
+    /// let sq = Square::keyed_5x5("JAZZ");
+    /// assert_eq!(sq.position('J'), Some((0, 0)));
+    /// assert_eq!(sq.get(0, 1), Some('A'));
+    /// ```
+    pub fn keyed_5x5<K: Text>(keyword: K) -> Self {
+        let key: String = letters(keyword)
+            .into_iter()
+            .map(|c| if c == b'J' { 'I' } else { c as char })
+            .collect();
+        Self::from_keyword(key, ALPHABET_NO_J)
+            .unwrap()
+            .with_merge('J', 'I')
+    }
+
+    /// Returns a copy of this square in which `from` is treated as `to`.
+    pub fn with_merge(mut self, from: char, to: char) -> Self {
+        self.merges.push((
+            from.to_ascii_uppercase() as u8,
+            to.to_ascii_uppercase() as u8,
+        ));
+        self
+    }
+
+    /// The number of rows (and columns) of the square.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the symbol in the given row and column (starting from 0).
+    pub fn get(&self, row: usize, col: usize) -> Option<char> {
+        if row < self.size && col < self.size {
+            Some(self.cells[row * self.size + col] as char)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the row and column (starting from 0) of the symbol, after
+    /// converting it to uppercase and applying merges.
+    pub fn position<L: crate::letter::Letter>(&self, c: L) -> Option<(usize, usize)> {
+        let c = self.normalize(c.byte())?;
+        let idx = self.cells.iter().position(|&d| d == c)?;
+        Some((idx / self.size, idx % self.size))
+    }
+
+    pub(crate) fn normalize(&self, c: u8) -> Option<u8> {
+        let c = c.to_ascii_uppercase();
+        let c = self.merges.iter().find(|m| m.0 == c).map_or(c, |m| m.1);
+        if self.cells.contains(&c) {
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn at(&self, row: usize, col: usize) -> u8 {
+        self.cells[row * self.size + col]
+    }
+
+    /// Returns the symbols of `s` that appear in the square (after
+    /// applying merges), discarding everything else.
+    pub(crate) fn filter<S: Text>(&self, s: S) -> Vec<u8> {
+        s.bytes().filter_map(|c| self.normalize(c)).collect()
+    }
+}
+
+impl std::fmt::Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (n, row) in self.cells.chunks(self.size).enumerate() {
+            if n > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(std::str::from_utf8(row).unwrap())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,571 @@
+//! Transposition ciphers, which rearrange the letters of a message.
+//!
+//! These ciphers discard everything other than letters, so their output
+//! is uppercase with no spaces.
+
+use super::{letters, rank_decryptions, Decryption, Scorer};
+use crate::letter_counts::LetterCounts;
+use crate::search::{sort_results, SearchResult};
+use crate::word::Text;
+use crate::wordlist::{Wordlist, WordlistEntry};
+use std::fmt::{self, Display};
+
+// Reads the letters of `v` in the given order.
+fn permute(v: &[u8], order: &[usize]) -> String {
+    order.iter().map(|&i| v[i] as char).collect()
+}
+
+// Undoes `permute`.
+fn unpermute(v: &[u8], order: &[usize]) -> String {
+    let mut out = vec![0; v.len()];
+    for (&c, &i) in v.iter().zip(order) {
+        out[i] = c;
+    }
+    out.into_iter().map(char::from).collect()
+}
+
+// Returns all permutations of 0..n, in lexicographic order.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    let mut v = vec![(0..n).collect::<Vec<_>>()];
+    let mut p = v[0].clone();
+    loop {
+        let Some(i) = (1..n).rev().find(|&i| p[i - 1] < p[i]) else {
+            return v;
+        };
+        let j = (i..n).rev().find(|&j| p[j] > p[i - 1]).unwrap();
+        p.swap(i - 1, j);
+        p[i..].reverse();
+        v.push(p.clone());
+    }
+}
+
+/// The rail fence cipher.  The plaintext is written in a zigzag on
+/// `rails` rows, starting `offset` places into the zigzag, and the rows
+/// are read off from top to bottom.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RailFence {
+    pub rails: usize,
+    pub offset: usize,
+}
+
+impl RailFence {
+    pub fn new(rails: usize, offset: usize) -> Self {
+        Self { rails, offset }
+    }
+
+    fn order(&self, len: usize) -> Vec<usize> {
+        let cycle = (2 * self.rails).saturating_sub(2).max(1);
+        let row = |i: usize| {
+            let p = (i + self.offset) % cycle;
+            p.min(cycle - p)
+        };
+        let mut order: Vec<usize> = (0..len).collect();
+        order.sort_by_key(|&i| row(i));
+        order
+    }
+
+    /// ```
+    /// use puzzletools::cipher::transposition::RailFence;
+    /// let r = RailFence::new(3, 0);
+    /// assert_eq!(r.encrypt("We are discovered, flee at once"), "WECRLTEERDSOEEFEAOCAIVDEN");
+    /// assert_eq!(RailFence::new(3, 1).encrypt("We are discovered"), "RSEWAEICVRDEDOE");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let v = letters(s);
+        permute(&v, &self.order(v.len()))
+    }
+
+    /// ```
+    /// use puzzletools::cipher::transposition::RailFence;
+    /// let r = RailFence::new(3, 0);
+    /// assert_eq!(r.decrypt("WECRLTEERDSOEEFEAOCAIVDEN"), "WEAREDISCOVEREDFLEEATONCE");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let v = letters(s);
+        unpermute(&v, &self.order(v.len()))
+    }
+}
+
+impl Display for RailFence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rails={}, offset={}", self.rails, self.offset)
+    }
+}
+
+/// Returns the order in which the columns of a columnar transposition
+/// are read, given a keyword: the column under the alphabetically first
+/// letter is read first, and ties are broken from left to right.  The
+/// result gives the position of each column in the reading order,
+/// starting from 0.
+/// ```
+/// use puzzletools::cipher::transposition::keyword_order;
+/// assert_eq!(keyword_order("ZEBRAS"), vec![5, 2, 1, 3, 0, 4]);
+/// assert_eq!(keyword_order("BANANA"), vec![3, 0, 4, 1, 5, 2]);
+/// ```
+pub fn keyword_order<K: Text>(keyword: K) -> Vec<usize> {
+    let v = letters(keyword);
+    let mut columns: Vec<usize> = (0..v.len()).collect();
+    columns.sort_by_key(|&i| v[i]);
+    let mut order = vec![0; v.len()];
+    for (n, &c) in columns.iter().enumerate() {
+        order[c] = n;
+    }
+    order
+}
+
+/// A columnar transposition.  The plaintext is written in rows under the
+/// key, and the columns are read off in the order given by the key.  The
+/// last row may be incomplete.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Columnar {
+    // the position of each column in the reading order
+    order: Vec<usize>,
+}
+
+impl Columnar {
+    /// Creates a columnar transposition from a numeric key, which gives
+    /// the position of each column in the reading order, numbered from 1.
+    /// Returns `None` if the key is not a permutation of 1 through n.
+    /// ```
+    /// use puzzletools::cipher::transposition::Columnar;
+    /// let c = Columnar::new(&[3, 1, 2]).unwrap();
+    /// assert_eq!(c.encrypt("ABCDEFG"), "BECFADG");
+    /// assert!(Columnar::new(&[0, 1]).is_none());
+    /// ```
+    pub fn new(key: &[usize]) -> Option<Self> {
+        let mut sorted = key.to_vec();
+        sorted.sort_unstable();
+        if sorted.iter().enumerate().any(|(n, &k)| k != n + 1) {
+            return None;
+        }
+        Some(Self {
+            order: key.iter().map(|k| k - 1).collect(),
+        })
+    }
+
+    /// Creates a columnar transposition from a keyword (see
+    /// `keyword_order`).
+    pub fn from_keyword<K: Text>(keyword: K) -> Self {
+        Self {
+            order: keyword_order(keyword),
+        }
+    }
+
+    /// The number of columns.
+    pub fn width(&self) -> usize {
+        self.order.len()
+    }
+
+    fn reading_order(&self, len: usize) -> Vec<usize> {
+        let width = self.width();
+        if width == 0 {
+            return (0..len).collect();
+        }
+        let mut columns: Vec<usize> = (0..width).collect();
+        columns.sort_by_key(|&c| self.order[c]);
+        columns
+            .into_iter()
+            .flat_map(|c| (c..len).step_by(width))
+            .collect()
+    }
+
+    /// ```
+    /// use puzzletools::cipher::transposition::Columnar;
+    /// let c = Columnar::from_keyword("ZEBRAS");
+    /// assert_eq!(c.encrypt("We are discovered, flee at once"), "EVLNACDTESEAROFODEECWIREE");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let v = letters(s);
+        permute(&v, &self.reading_order(v.len()))
+    }
+
+    /// ```
+    /// use puzzletools::cipher::transposition::Columnar;
+    /// let c = Columnar::from_keyword("ZEBRAS");
+    /// assert_eq!(c.decrypt("EVLNACDTESEAROFODEECWIREE"), "WEAREDISCOVEREDFLEEATONCE");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let v = letters(s);
+        unpermute(&v, &self.reading_order(v.len()))
+    }
+}
+
+impl Display for Columnar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (n, k) in self.order.iter().enumerate() {
+            if n > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", k + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// A double columnar transposition, which applies two columnar
+/// transpositions in succession.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DoubleColumnar {
+    pub first: Columnar,
+    pub second: Columnar,
+}
+
+impl DoubleColumnar {
+    /// Creates a double columnar transposition from two keywords.
+    pub fn from_keywords<K: Text, L: Text>(first: K, second: L) -> Self {
+        Self {
+            first: Columnar::from_keyword(first),
+            second: Columnar::from_keyword(second),
+        }
+    }
+
+    /// ```
+    /// use puzzletools::cipher::transposition::DoubleColumnar;
+    /// let d = DoubleColumnar::from_keywords("ZEBRAS", "STRIPE");
+    /// assert_eq!(d.encrypt("We are discovered, flee at once"), "CAEENSOIAEDRLEFWEDREEVTOC");
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        self.second.encrypt(self.first.encrypt(s))
+    }
+
+    /// ```
+    /// use puzzletools::cipher::transposition::DoubleColumnar;
+    /// let d = DoubleColumnar::from_keywords("ZEBRAS", "STRIPE");
+    /// assert_eq!(d.decrypt("CAEENSOIAEDRLEFWEDREEVTOC"), "WEAREDISCOVEREDFLEEATONCE");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        self.first.decrypt(self.second.decrypt(s))
+    }
+}
+
+/// The path used to read off the grid in a route cipher.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Path {
+    /// Down each column, from left to right.
+    Columns,
+    /// Down the first column, up the second, and so on.
+    AlternateColumns,
+    /// Clockwise from the top left corner, spiraling inward.
+    Spiral,
+    /// Counterclockwise from the top left corner, spiraling inward.
+    CounterclockwiseSpiral,
+}
+
+impl Path {
+    /// All of the paths.
+    pub const ALL: [Path; 4] = [
+        Path::Columns,
+        Path::AlternateColumns,
+        Path::Spiral,
+        Path::CounterclockwiseSpiral,
+    ];
+}
+
+/// A route cipher.  The plaintext is written in rows of the given width,
+/// and read off along the path.  Cells of an incomplete last row are
+/// skipped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Route {
+    pub width: usize,
+    pub path: Path,
+}
+
+impl Route {
+    pub fn new(width: usize, path: Path) -> Self {
+        Self { width, path }
+    }
+
+    fn order(&self, len: usize) -> Vec<usize> {
+        let width = self.width.max(1);
+        let height = len.div_ceil(width);
+        let cells: Vec<(usize, usize)> = match self.path {
+            Path::Columns => (0..width)
+                .flat_map(|c| (0..height).map(move |r| (r, c)))
+                .collect(),
+            Path::AlternateColumns => (0..width)
+                .flat_map(|c| {
+                    (0..height).map(move |r| {
+                        if c % 2 == 0 {
+                            (r, c)
+                        } else {
+                            (height - 1 - r, c)
+                        }
+                    })
+                })
+                .collect(),
+            Path::Spiral => spiral(height, width),
+            Path::CounterclockwiseSpiral => spiral(width, height)
+                .into_iter()
+                .map(|(r, c)| (c, r))
+                .collect(),
+        };
+        cells
+            .into_iter()
+            .map(|(r, c)| r * width + c)
+            .filter(|&i| i < len)
+            .collect()
+    }
+
+    /// ```
+    /// use puzzletools::cipher::transposition::{Path, Route};
+    /// assert_eq!(Route::new(4, Path::Columns).encrypt("ABCDEFGHIJKL"), "AEIBFJCGKDHL");
+    /// assert_eq!(Route::new(4, Path::AlternateColumns).encrypt("ABCDEFGHIJKL"), "AEIJFBCGKLHD");
+    /// assert_eq!(Route::new(4, Path::Spiral).encrypt("ABCDEFGHIJKL"), "ABCDHLKJIEFG");
+    /// assert_eq!(
+    ///     Route::new(4, Path::CounterclockwiseSpiral).encrypt("ABCDEFGHIJKL"),
+    ///     "AEIJKLHDCBFG"
+    /// );
+    /// ```
+    pub fn encrypt<S: Text>(&self, s: S) -> String {
+        let v = letters(s);
+        permute(&v, &self.order(v.len()))
+    }
+
+    /// ```
+    /// use puzzletools::cipher::transposition::{Path, Route};
+    /// assert_eq!(Route::new(4, Path::Spiral).decrypt("ABCDHLKJIEFG"), "ABCDEFGHIJKL");
+    /// ```
+    pub fn decrypt<S: Text>(&self, s: S) -> String {
+        let v = letters(s);
+        unpermute(&v, &self.order(v.len()))
+    }
+}
+
+impl Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "width={}, path={:?}", self.width, self.path)
+    }
+}
+
+// The cells of a grid, in clockwise spiral order from the top left.
+fn spiral(height: usize, width: usize) -> Vec<(usize, usize)> {
+    let mut v = Vec::with_capacity(height * width);
+    let (mut top, mut left) = (0, 0);
+    let (mut bottom, mut right) = (height, width);
+    while top < bottom && left < right {
+        v.extend((left..right).map(|c| (top, c)));
+        v.extend((top + 1..bottom).map(|r| (r, right - 1)));
+        if top + 1 < bottom && left + 1 < right {
+            v.extend((left..right - 1).rev().map(|c| (bottom - 1, c)));
+            v.extend((top + 1..bottom - 1).rev().map(|r| (r, left)));
+        }
+        top += 1;
+        left += 1;
+        bottom -= 1;
+        right -= 1;
+    }
+    v
+}
+
+/// Tries all rail fence ciphers with up to `max_rails` rails.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::transposition::{rail_fence_brute_force, RailFence};
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("ATTACK,5\nAT,10\nDAWN,3")).unwrap();
+/// let best = rail_fence_brute_force("ATNATCADWTKA", 5, &wl).next().unwrap();
+/// assert_eq!(best.key, RailFence::new(3, 1));
+/// assert_eq!(best.plaintext, "ATTACKATDAWN");
+/// ```
+pub fn rail_fence_brute_force<S: Text, C: Scorer>(
+    s: S,
+    max_rails: usize,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<RailFence>> {
+    let candidates: Vec<_> = (2..=max_rails)
+        .flat_map(|rails| (0..2 * rails - 2).map(move |offset| RailFence::new(rails, offset)))
+        .map(|r| (r, r.decrypt(s.as_str())))
+        .collect();
+    rank_decryptions(candidates, scorer)
+}
+
+/// Tries all columnar transpositions with up to `max_width` columns.
+/// There are `n!` keys with `n` columns, so `max_width` should be at most
+/// about 8.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::transposition::columnar_brute_force;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("ATTACK,5\nAT,10\nDAWN,3")).unwrap();
+/// let best = columnar_brute_force("ATNTKAACDTAW", 4, &wl).next().unwrap();
+/// assert_eq!(best.key.to_string(), "3 2 4 1");
+/// assert_eq!(best.plaintext, "ATTACKATDAWN");
+/// ```
+pub fn columnar_brute_force<S: Text, C: Scorer>(
+    s: S,
+    max_width: usize,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<Columnar>> {
+    let candidates: Vec<_> = (1..=max_width)
+        .flat_map(permutations)
+        .map(|order| {
+            let c = Columnar { order };
+            let p = c.decrypt(s.as_str());
+            (c, p)
+        })
+        .collect();
+    rank_decryptions(candidates, scorer)
+}
+
+/// Tries all route ciphers.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::transposition::{route_brute_force, Path, Route};
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("ATTACK,5\nAT,10\nDAWN,3")).unwrap();
+/// let best = route_brute_force("ATTATNWADCKA", &wl).next().unwrap();
+/// assert_eq!(best.key, Route::new(4, Path::Spiral));
+/// assert_eq!(best.plaintext, "ATTACKATDAWN");
+/// ```
+pub fn route_brute_force<S: Text, C: Scorer>(
+    s: S,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<Route>> {
+    let len = letters(s.as_str()).len();
+    let candidates: Vec<_> = (2..len)
+        .flat_map(|width| Path::ALL.iter().map(move |&path| Route::new(width, path)))
+        .map(|r| (r, r.decrypt(s.as_str())))
+        .collect();
+    rank_decryptions(candidates, scorer)
+}
+
+/// A solution to a multiple anagramming problem.
+#[derive(Clone, Debug)]
+pub struct MultipleAnagram<'a> {
+    /// The rearrangement: letter `i` of each word is letter
+    /// `permutation[i]` of the corresponding string.
+    pub permutation: Vec<usize>,
+    pub words: Vec<&'a WordlistEntry>,
+}
+
+impl SearchResult for MultipleAnagram<'_> {
+    type Data = String;
+    type Freq = u64;
+    fn data(&self) -> String {
+        let words: Vec<&str> = self.words.iter().map(|w| w.word.as_str()).collect();
+        words.join(" ")
+    }
+    /// The frequency of the least common word.
+    fn freq(&self) -> u64 {
+        self.words.iter().map(|w| w.freq).min().unwrap_or(0)
+    }
+}
+
+/// Finds the ways to rearrange the letters of several strings of the same
+/// length, using the same permutation for each string, so that all of
+/// them become words in the wordlist.  This is the classic attack on a
+/// transposition cipher when several messages of the same length were
+/// sent with the same key.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::transposition::multiple_anagram;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("RATS,1\nSTAR,1\nARTS,1\nTSAR,1\nLIME,1\nMILE,1")).unwrap();
+/// let v: Vec<_> = multiple_anagram(&["TRAS", "MELI"], &wl).collect();
+/// assert_eq!(v.len(), 1);
+/// assert_eq!(v[0].permutation, vec![0, 3, 2, 1]);
+/// assert_eq!(v[0].words[0].word, "TSAR");
+/// assert_eq!(v[0].words[1].word, "MILE");
+/// ```
+pub fn multiple_anagram<'a, S: Text>(
+    strings: &[S],
+    wl: &'a Wordlist,
+) -> impl Iterator<Item = MultipleAnagram<'a>> {
+    let strings: Vec<Vec<u8>> = strings.iter().map(|s| letters(s.as_str())).collect();
+    let len = strings.first().map_or(0, Vec::len);
+    let mut results = Vec::new();
+    if len > 0 && strings.iter().all(|s| s.len() == len) {
+        let counts: Vec<LetterCounts> = strings
+            .iter()
+            .map(|s| LetterCounts::from_text(&s[..]))
+            .collect();
+        let mut candidates = vec![Vec::new(); strings.len()];
+        for e in wl.iter().filter(|e| e.len() == len) {
+            let a = LetterCounts::from_text(&e.slug);
+            for (k, s) in counts.iter().enumerate() {
+                if *s == a {
+                    candidates[k].push(e);
+                }
+            }
+        }
+        let mut permutation = Vec::with_capacity(len);
+        let mut used = vec![false; len];
+        extend_anagram(
+            &strings,
+            &candidates,
+            &mut permutation,
+            &mut used,
+            &mut results,
+        );
+        // repeated letters give several permutations with the same words
+        results.sort_by_cached_key(|m| m.words.iter().map(|w| w.slug.clone()).collect::<Vec<_>>());
+        results.dedup_by(|a, b| a.words == b.words);
+    }
+    sort_results(results.into_iter())
+}
+
+// Chooses the next position of the permutation, keeping track of the
+// words that are consistent with the choices made so far.
+fn extend_anagram<'a>(
+    strings: &[Vec<u8>],
+    candidates: &[Vec<&'a WordlistEntry>],
+    permutation: &mut Vec<usize>,
+    used: &mut [bool],
+    results: &mut Vec<MultipleAnagram<'a>>,
+) {
+    let i = permutation.len();
+    if i == used.len() {
+        results.push(MultipleAnagram {
+            permutation: permutation.clone(),
+            words: candidates.iter().map(|c| c[0]).collect(),
+        });
+        return;
+    }
+    for j in 0..used.len() {
+        if used[j] {
+            continue;
+        }
+        let next: Vec<Vec<&WordlistEntry>> = candidates
+            .iter()
+            .zip(strings)
+            .map(|(c, s)| {
+                c.iter()
+                    .filter(|e| e.slug.as_bytes()[i] == s[j])
+                    .copied()
+                    .collect()
+            })
+            .collect();
+        if next.iter().any(Vec::is_empty) {
+            continue;
+        }
+        used[j] = true;
+        permutation.push(j);
+        extend_anagram(strings, &next, permutation, used, results);
+        permutation.pop();
+        used[j] = false;
+    }
+}
+
+#[test]
+fn transposition_roundtrip_test() {
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG";
+    for rails in 1..6 {
+        for offset in 0..8 {
+            let r = RailFence::new(rails, offset);
+            assert_eq!(r.decrypt(r.encrypt(text)), text);
+        }
+    }
+    for width in 1..8 {
+        for order in permutations(width) {
+            let c = Columnar { order };
+            assert_eq!(c.decrypt(c.encrypt(text)), text);
+        }
+        for path in Path::ALL {
+            let r = Route::new(width, path);
+            let mut order = r.order(text.len());
+            order.sort_unstable();
+            assert_eq!(order, (0..text.len()).collect::<Vec<_>>());
+            assert_eq!(r.decrypt(r.encrypt(text)), text);
+        }
+    }
+}
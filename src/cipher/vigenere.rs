@@ -0,0 +1,316 @@
+//! Vigenère and related polyalphabetic ciphers, and tools for recovering
+//! their keys.
+
+use super::{
+    index_of_coincidence, letters, map_letters, rank_decryptions, try_keys, Decryption, Scorer,
+};
+use crate::letter::ENGLISH_FREQUENCIES;
+use crate::word::Text;
+use std::collections::HashSet;
+
+/// The rule used to combine a plaintext letter with a key letter.
+/// Letters are numbered starting from A = 0.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tableau {
+    /// ciphertext = plaintext + key
+    Vigenere,
+    /// ciphertext = key - plaintext
+    Beaufort,
+    /// ciphertext = plaintext - key
+    VariantBeaufort,
+}
+
+impl Tableau {
+    fn encrypt_letter(self, p: usize, k: usize) -> usize {
+        match self {
+            Tableau::Vigenere => (p + k) % 26,
+            Tableau::Beaufort => (k + 26 - p) % 26,
+            Tableau::VariantBeaufort => (p + 26 - k) % 26,
+        }
+    }
+
+    fn decrypt_letter(self, c: usize, k: usize) -> usize {
+        match self {
+            Tableau::Vigenere => (c + 26 - k) % 26,
+            Tableau::Beaufort => (k + 26 - c) % 26,
+            Tableau::VariantBeaufort => (c + k) % 26,
+        }
+    }
+
+    /// Encrypts `s` using a repeating key.  The key only advances on
+    /// letters.  A running key cipher is the special case where the key
+    /// is a text at least as long as the plaintext.
+    /// ```
+    /// use puzzletools::cipher::vigenere::Tableau;
+    /// assert_eq!(Tableau::Vigenere.encrypt("Attack at dawn", "LEMON"), "Lxfopv ef rnhr");
+    /// assert_eq!(
+    ///     Tableau::Beaufort.encrypt("DEFENDTHEEASTWALLOFTHECASTLE", "FORTIFICATION"),
+    ///     "CKMPVCPVWPIWUJOGIUAPVWRIWUUK"
+    /// );
+    /// ```
+    pub fn encrypt<S: Text, K: Text>(self, s: S, key: K) -> String {
+        let key = key_numbers(key);
+        let mut i = 0;
+        map_letters(s, |n| {
+            let k = key[i % key.len()];
+            i += 1;
+            self.encrypt_letter(n, k)
+        })
+    }
+
+    /// Decrypts `s` using a repeating key.
+    /// ```
+    /// use puzzletools::cipher::vigenere::Tableau;
+    /// assert_eq!(Tableau::Vigenere.decrypt("Lxfopv ef rnhr", "LEMON"), "Attack at dawn");
+    /// assert_eq!(Tableau::VariantBeaufort.decrypt("Pphmpz wh pnlj", "LEMON"), "Attack at dawn");
+    /// ```
+    pub fn decrypt<S: Text, K: Text>(self, s: S, key: K) -> String {
+        let key = key_numbers(key);
+        let mut i = 0;
+        map_letters(s, |n| {
+            let k = key[i % key.len()];
+            i += 1;
+            self.decrypt_letter(n, k)
+        })
+    }
+
+    /// Encrypts `s` with an autokey cipher: the key is the primer followed
+    /// by the plaintext.
+    /// ```
+    /// use puzzletools::cipher::vigenere::Tableau;
+    /// assert_eq!(Tableau::Vigenere.autokey_encrypt("ATTACKATDAWN", "QUEENLY"), "QNXEPVYTWTWP");
+    /// ```
+    pub fn autokey_encrypt<S: Text, K: Text>(self, s: S, primer: K) -> String {
+        let mut key = key_numbers(primer);
+        let mut i = 0;
+        map_letters(s, |n| {
+            key.push(n);
+            let k = key[i];
+            i += 1;
+            self.encrypt_letter(n, k)
+        })
+    }
+
+    /// Decrypts `s` with an autokey cipher.
+    /// ```
+    /// use puzzletools::cipher::vigenere::Tableau;
+    /// assert_eq!(Tableau::Vigenere.autokey_decrypt("QNXEPVYTWTWP", "QUEENLY"), "ATTACKATDAWN");
+    /// ```
+    pub fn autokey_decrypt<S: Text, K: Text>(self, s: S, primer: K) -> String {
+        let mut key = key_numbers(primer);
+        let mut i = 0;
+        map_letters(s, |n| {
+            let p = self.decrypt_letter(n, key[i]);
+            key.push(p);
+            i += 1;
+            p
+        })
+    }
+}
+
+// The letters of the key, as numbers.  An empty key acts like "A".
+fn key_numbers<K: Text>(key: K) -> Vec<usize> {
+    let mut v: Vec<usize> = letters(key).iter().map(|&c| (c - b'A') as usize).collect();
+    if v.is_empty() {
+        v.push(0);
+    }
+    v
+}
+
+/// Returns the average index of coincidence of the columns of `s` when
+/// its letters are written in rows of length `period`.  For a periodic
+/// cipher, this is close to the English value (about 0.066) when the
+/// period is a multiple of the key length.  Returns 0 if `period` is 0.
+/// ```
+/// use puzzletools::cipher::vigenere::period_index_of_coincidence;
+/// assert_eq!(period_index_of_coincidence("AABB", 2), 0.0);
+/// assert_eq!(period_index_of_coincidence("ABAB", 2), 1.0);
+/// assert_eq!(period_index_of_coincidence("ABAB", 0), 0.0);
+/// ```
+pub fn period_index_of_coincidence<S: Text>(s: S, period: usize) -> f64 {
+    if period == 0 {
+        return 0.0;
+    }
+    let v = letters(s);
+    let total: f64 = (0..period)
+        .map(|start| {
+            let column: Vec<u8> = v.iter().skip(start).step_by(period).copied().collect();
+            index_of_coincidence(&column)
+        })
+        .sum();
+    total / period as f64
+}
+
+/// Returns the periods from 1 to `max_period`, ordered from most to least
+/// likely by the index of coincidence of their columns.
+pub fn likely_periods<S: Text>(s: S, max_period: usize) -> Vec<(usize, f64)> {
+    let mut v: Vec<_> = (1..=max_period)
+        .map(|p| (p, period_index_of_coincidence(s.as_str(), p)))
+        .collect();
+    v.sort_by(|a, b| b.1.total_cmp(&a.1));
+    v
+}
+
+/// Finds the most likely key of the given length, by choosing each key
+/// letter so that the letter frequencies of the corresponding column of
+/// plaintext are as close as possible to English.  This needs a
+/// ciphertext with at least a few dozen letters per key letter.
+/// ```
+/// use puzzletools::cipher::vigenere::{recover_key, Tableau};
+/// let plaintext = "It was the best of times, it was the worst of times, it was the age of \
+///     wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch \
+///     of incredulity, it was the season of Light, it was the season of Darkness, it was \
+///     the spring of hope, it was the winter of despair, we had everything before us, we \
+///     had nothing before us, we were all going direct to Heaven, we were all going direct \
+///     the other way.";
+/// let ciphertext = Tableau::Vigenere.encrypt(plaintext, "DICKENS");
+/// assert_eq!(recover_key(&ciphertext, 7, Tableau::Vigenere), "DICKENS");
+/// ```
+pub fn recover_key<S: Text>(s: S, period: usize, tableau: Tableau) -> String {
+    let v = letters(s);
+    (0..period)
+        .map(|start| {
+            let mut counts = [0f64; 26];
+            let mut total = 0f64;
+            for &c in v.iter().skip(start).step_by(period) {
+                counts[(c - b'A') as usize] += 1.0;
+                total += 1.0;
+            }
+            let k = (0..26)
+                .min_by(|&k1, &k2| {
+                    let c1 = chi_squared(&counts, total, |c| tableau.decrypt_letter(c, k1));
+                    let c2 = chi_squared(&counts, total, |c| tableau.decrypt_letter(c, k2));
+                    c1.total_cmp(&c2)
+                })
+                .unwrap();
+            (b'A' + k as u8) as char
+        })
+        .collect()
+}
+
+// Compares the letter counts of a column, after decryption, to English.
+// An empty column has a chi-squared statistic of 0.
+pub(super) fn chi_squared<F: Fn(usize) -> usize>(
+    counts: &[f64; 26],
+    total: f64,
+    decrypt: F,
+) -> f64 {
+    if total <= 0.0 {
+        return 0.0;
+    }
+    (0..26)
+        .map(|c| {
+            let expected = total * ENGLISH_FREQUENCIES[decrypt(c)];
+            (counts[c] - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Tries to break a periodic cipher by recovering the most likely key of
+/// each length up to `max_period`, and ranks the resulting plaintexts.
+/// ```
+/// use puzzletools::cipher::vigenere::{break_periodic, Tableau};
+/// use puzzletools::cipher::NgramScorer;
+/// use puzzletools::wordlist::Wordlist;
+/// let plaintext = "It was the best of times, it was the worst of times, it was the age of \
+///     wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch \
+///     of incredulity, it was the season of Light, it was the season of Darkness, it was \
+///     the spring of hope, it was the winter of despair, we had everything before us, we \
+///     had nothing before us, we were all going direct to Heaven, we were all going direct \
+///     the other way.";
+/// let wl: Wordlist = plaintext
+///     .split(|c: char| !c.is_alphabetic())
+///     .filter(|w| !w.is_empty())
+///     .map(|w| puzzletools::wordlist::WordFreq { word: w.to_uppercase(), freq: 1 })
+///     .collect();
+/// let scorer = NgramScorer::from_wordlist(&wl, 3);
+/// let ciphertext = Tableau::Beaufort.encrypt(plaintext, "TWOCITIES");
+/// let best = break_periodic(&ciphertext, Tableau::Beaufort, 12, &scorer).next().unwrap();
+/// assert_eq!(best.key, "TWOCITIES");
+/// assert_eq!(best.plaintext, plaintext);
+/// ```
+pub fn break_periodic<S: Text, C: Scorer>(
+    s: S,
+    tableau: Tableau,
+    max_period: usize,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<String>> {
+    let mut seen = HashSet::new();
+    let candidates: Vec<_> = (1..=max_period)
+        .map(|p| recover_key(s.as_str(), p, tableau))
+        .filter_map(|key| {
+            let plaintext = tableau.decrypt(s.as_str(), &key);
+            if seen.insert(plaintext.clone()) {
+                Some((key, plaintext))
+            } else {
+                None
+            }
+        })
+        .collect();
+    rank_decryptions(candidates, scorer)
+}
+
+/// Tries each of the given keys (for example, every entry of a wordlist)
+/// with a repeating key, and ranks the resulting plaintexts.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::cipher::vigenere::{dictionary_attack, Tableau};
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("ATTACK,5\nAT,10\nDAWN,3\nLEMON,1")).unwrap();
+/// let keys = wl.iter().map(|w| &w.slug);
+/// let best = dictionary_attack("LXFOPVEFRNHR", Tableau::Vigenere, keys, &wl).next().unwrap();
+/// assert_eq!(best.key, "LEMON");
+/// assert_eq!(best.plaintext, "ATTACKATDAWN");
+/// ```
+pub fn dictionary_attack<S, I, C>(
+    s: S,
+    tableau: Tableau,
+    keys: I,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<String>>
+where
+    S: Text,
+    I: IntoIterator,
+    I::Item: Text,
+    C: Scorer,
+{
+    try_keys(s, keys, move |k, s| tableau.decrypt(s, k), scorer)
+}
+
+/// Tries each of the given primers with an autokey cipher, and ranks the
+/// resulting plaintexts.
+pub fn autokey_dictionary_attack<S, I, C>(
+    s: S,
+    tableau: Tableau,
+    primers: I,
+    scorer: &C,
+) -> impl Iterator<Item = Decryption<String>>
+where
+    S: Text,
+    I: IntoIterator,
+    I::Item: Text,
+    C: Scorer,
+{
+    try_keys(
+        s,
+        primers,
+        move |k, s| tableau.autokey_decrypt(s, k),
+        scorer,
+    )
+}
+
+#[test]
+fn tableau_roundtrip_test() {
+    let text = "The quick brown fox jumps over the lazy dog!";
+    for t in [
+        Tableau::Vigenere,
+        Tableau::Beaufort,
+        Tableau::VariantBeaufort,
+    ] {
+        assert_eq!(t.decrypt(t.encrypt(text, "KEY"), "KEY"), text);
+        assert_eq!(
+            t.autokey_decrypt(t.autokey_encrypt(text, "KEY"), "KEY"),
+            text
+        );
+    }
+}
@@ -1,4 +1,11 @@
-//! Codes (Morse, amino acids)
+//! Codes (Morse, amino acids, Braille, semaphore, binary, phone keypads,
+//! segment displays)
+
+pub mod binary;
+pub mod genetic;
+pub mod keypad;
+pub mod segment;
+pub mod semaphore;
 
 use crate::letter::{lett_to_num_0, Letter};
 use crate::word::Text;
@@ -96,3 +103,38 @@ fn braille_bits<L: Letter>(l: L) -> u8 {
 pub fn braille_distance<L: Letter, M: Letter>(l1: L, l2: M) -> u32 {
     (braille_bits(l1) ^ braille_bits(l2)).count_ones()
 }
+
+/// Returns the number of segments that must change to turn the
+/// seven-segment display of one character into the other, or `None` if
+/// one of them cannot be displayed.
+/// ```
+/// use puzzletools::code::seven_segment_distance;
+/// assert_eq!(seven_segment_distance('8','0'),Some(1));
+/// assert_eq!(seven_segment_distance('1','7'),Some(1));
+/// assert_eq!(seven_segment_distance('A','M'),None);
+/// ```
+pub fn seven_segment_distance<L: Letter, M: Letter>(l1: L, l2: M) -> Option<u32> {
+    segment::SegmentDisplay::Seven.distance(l1, l2)
+}
+
+/// Returns the number of arms that must move to change the signal for
+/// one letter into the signal for the other, or `None` if one of them
+/// has no semaphore signal.
+/// ```
+/// use puzzletools::code::semaphore_distance;
+/// assert_eq!(semaphore_distance('A', 'A'), Some(0));
+/// assert_eq!(semaphore_distance('A', 'B'), Some(1));
+/// assert_eq!(semaphore_distance('A', 'T'), Some(2));
+/// assert_eq!(semaphore_distance('A', '!'), None);
+/// ```
+pub fn semaphore_distance<L: Letter, M: Letter>(l1: L, l2: M) -> Option<u32> {
+    let (a1, b1) = semaphore::to_semaphore(l1)?;
+    let (a2, b2) = semaphore::to_semaphore(l2)?;
+    Some(if (a1, b1) == (a2, b2) {
+        0
+    } else if a1 == a2 || a1 == b2 || b1 == a2 || b1 == b2 {
+        1
+    } else {
+        2
+    })
+}
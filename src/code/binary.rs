@@ -0,0 +1,375 @@
+//! Encodings of letters as bits: 5-bit binary, ASCII, Baconian and
+//! ITA2 (Baudot).
+//!
+//! Bits are represented as `bool`s, with `true` for 1, and the most
+//! significant bit comes first.  Bits can be read from a string of
+//! digits with `parse_bits`, or from a property of the letters of a word
+//! with `read_bits`:
+//! ```
+//! use puzzletools::code::binary::{decode_a1z26, read_bits};
+//! use puzzletools::letter::is_vowel_y;
+//! // consonants are 0 and vowels are 1
+//! let bits = read_bits("STRAW THEME", is_vowel_y);
+//! assert_eq!(decode_a1z26(&bits), Some("BE".to_string()));
+//! ```
+
+use crate::letter::{lett_to_num_1, num_to_lett_1};
+use crate::word::Text;
+
+/// Converts a sequence of bits to a number.  Returns `None` if the number
+/// does not fit in 64 bits.
+/// ```
+/// use puzzletools::code::binary::bits_to_number;
+/// assert_eq!(bits_to_number([true, false, true]), Some(5));
+/// assert_eq!(bits_to_number([true; 64]), Some(u64::MAX));
+/// assert_eq!(bits_to_number([true; 65]), None);
+/// ```
+pub fn bits_to_number<I: IntoIterator<Item = bool>>(bits: I) -> Option<u64> {
+    bits.into_iter()
+        .try_fold(0u64, |n, b| n.checked_mul(2)?.checked_add(b as u64))
+}
+
+/// Converts a number to a sequence of `width` bits (discarding any
+/// higher bits).
+/// ```
+/// use puzzletools::code::binary::number_to_bits;
+/// assert_eq!(number_to_bits(5, 4), vec![false, true, false, true]);
+/// ```
+pub fn number_to_bits(n: u64, width: usize) -> Vec<bool> {
+    (0..width)
+        .rev()
+        .map(|i| {
+            u32::try_from(i)
+                .ok()
+                .and_then(|i| n.checked_shr(i))
+                .unwrap_or(0)
+                & 1
+                == 1
+        })
+        .collect()
+}
+
+/// Reads bits from a string, where `zero` and `one` are the characters
+/// representing 0 and 1.  Other characters are ignored, and letters are
+/// compared without regard to case.
+/// ```
+/// use puzzletools::code::binary::parse_bits;
+/// assert_eq!(parse_bits("10 01", '0', '1'), vec![true, false, false, true]);
+/// assert_eq!(parse_bits("abba", 'A', 'B'), vec![false, true, true, false]);
+/// ```
+pub fn parse_bits<S: Text>(s: S, zero: char, one: char) -> Vec<bool> {
+    let (zero, one) = (zero.to_ascii_uppercase(), one.to_ascii_uppercase());
+    s.chars()
+        .map(|c| c.to_ascii_uppercase())
+        .filter_map(|c| {
+            if c == zero {
+                Some(false)
+            } else if c == one {
+                Some(true)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Writes bits as a string, using `zero` and `one` for 0 and 1.
+/// ```
+/// use puzzletools::code::binary::format_bits;
+/// assert_eq!(format_bits(&[false, true, true], 'A', 'B'), "ABB");
+/// ```
+pub fn format_bits(bits: &[bool], zero: char, one: char) -> String {
+    bits.iter().map(|&b| if b { one } else { zero }).collect()
+}
+
+/// Reads one bit from each letter of `s`: 1 if the letter satisfies the
+/// predicate and 0 otherwise.  Characters other than letters are skipped.
+/// ```
+/// use puzzletools::code::binary::read_bits;
+/// use puzzletools::letter::is_ascender;
+/// assert_eq!(read_bits("bat", is_ascender), vec![true, false, true]);
+/// ```
+pub fn read_bits<S: Text, F: FnMut(u8) -> bool>(s: S, mut pred: F) -> Vec<bool> {
+    s.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|c| pred(c.to_ascii_uppercase()))
+        .collect()
+}
+
+/// Like `read_bits`, but the function can also return `None` to skip a
+/// letter.
+/// ```
+/// use puzzletools::code::binary::read_bits_filtered;
+/// let f = |c| match c {
+///     b'O' => Some(false),
+///     b'I' => Some(true),
+///     _ => None,
+/// };
+/// assert_eq!(read_bits_filtered("OLIVINE", f), vec![false, true, true]);
+/// ```
+pub fn read_bits_filtered<S: Text, F: FnMut(u8) -> Option<bool>>(s: S, mut f: F) -> Vec<bool> {
+    s.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .filter_map(|c| f(c.to_ascii_uppercase()))
+        .collect()
+}
+
+/// Returns the letter whose number (A = 1) is the value of the bits.
+/// ```
+/// use puzzletools::code::binary::binary_letter;
+/// assert_eq!(binary_letter([true, false, true, true, true]), Some('W'));
+/// assert_eq!(binary_letter([false; 5]), None);
+/// ```
+pub fn binary_letter<I: IntoIterator<Item = bool>>(bits: I) -> Option<char> {
+    num_to_lett_1(bits_to_number(bits)? as usize)
+}
+
+/// Encodes each letter as the 5-bit binary representation of its number
+/// (A = 1).  Characters other than letters are skipped.
+/// ```
+/// use puzzletools::code::binary::{encode_a1z26, format_bits};
+/// assert_eq!(format_bits(&encode_a1z26("Hi"), '0', '1'), "0100001001");
+/// ```
+pub fn encode_a1z26<S: Text>(s: S) -> Vec<bool> {
+    s.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .flat_map(|c| number_to_bits(lett_to_num_1(c) as u64, 5))
+        .collect()
+}
+
+/// Decodes 5-bit binary letters (A = 1).  Returns `None` if the number of
+/// bits is not a multiple of 5, or if some group is not between 1 and 26.
+/// ```
+/// use puzzletools::code::binary::{decode_a1z26, parse_bits};
+/// let bits = parse_bits("01000 01001", '0', '1');
+/// assert_eq!(decode_a1z26(&bits), Some("HI".to_string()));
+/// ```
+pub fn decode_a1z26(bits: &[bool]) -> Option<String> {
+    if !bits.len().is_multiple_of(5) {
+        return None;
+    }
+    bits.chunks(5)
+        .map(|b| binary_letter(b.iter().copied()))
+        .collect()
+}
+
+/// Encodes each ASCII character as `width` bits (usually 7 or 8).
+/// Characters that do not fit are skipped.  Widths of 64 or more are
+/// allowed, and pad each character with leading zeros.
+/// ```
+/// use puzzletools::code::binary::{encode_ascii, format_bits};
+/// assert_eq!(format_bits(&encode_ascii("Hi", 7), '0', '1'), "10010001101001");
+/// ```
+pub fn encode_ascii<S: Text>(s: S, width: usize) -> Vec<bool> {
+    s.chars()
+        .filter(|&c| width >= 64 || (c as u64) < 1 << width)
+        .flat_map(|c| number_to_bits(c as u64, width))
+        .collect()
+}
+
+/// Decodes ASCII characters of `width` bits each.  Returns `None` if the
+/// number of bits is not a multiple of `width`, or if some group is not an
+/// ASCII character.
+/// ```
+/// use puzzletools::code::binary::{decode_ascii, parse_bits};
+/// let bits = parse_bits("01001000 01101001", '0', '1');
+/// assert_eq!(decode_ascii(&bits, 8), Some("Hi".to_string()));
+/// ```
+pub fn decode_ascii(bits: &[bool], width: usize) -> Option<String> {
+    if width == 0 || !bits.len().is_multiple_of(width) {
+        return None;
+    }
+    bits.chunks(width)
+        .map(|b| {
+            let n = bits_to_number(b.iter().copied())?;
+            if n < 128 {
+                Some(n as u8 as char)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// A version of the Baconian cipher, which encodes each letter as five
+/// bits, conventionally written with A for 0 and B for 1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Bacon {
+    /// Bacon's original alphabet, in which I and J share a code, as do U
+    /// and V.
+    Classic,
+    /// An alphabet in which each of the 26 letters has its own code
+    /// (A = 0 through Z = 25).
+    Full,
+}
+
+impl Bacon {
+    fn code(self, c: u8) -> u64 {
+        let n = c.to_ascii_uppercase() - b'A';
+        match self {
+            Bacon::Full => n as u64,
+            Bacon::Classic => (n - (c > b'I') as u8 - (c > b'U') as u8) as u64,
+        }
+    }
+
+    fn letter(self, n: u64) -> Option<char> {
+        let n = match self {
+            Bacon::Full if n < 26 => n as u8,
+            Bacon::Classic if n < 24 => n as u8 + (n >= 9) as u8 + (n >= 20) as u8,
+            _ => return None,
+        };
+        Some((b'A' + n) as char)
+    }
+
+    /// Encodes the letters of `s`.  Characters other than letters are
+    /// skipped.
+    /// ```
+    /// use puzzletools::code::binary::{format_bits, Bacon};
+    /// assert_eq!(format_bits(&Bacon::Classic.encode("Jive"), 'A', 'B'), "ABAAAABAAABAABBAABAA");
+    /// assert_eq!(format_bits(&Bacon::Full.encode("Jive"), 'A', 'B'), "ABAABABAAABABABAABAA");
+    /// ```
+    pub fn encode<S: Text>(self, s: S) -> Vec<bool> {
+        s.bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .flat_map(|c| number_to_bits(self.code(c.to_ascii_uppercase()), 5))
+            .collect()
+    }
+
+    /// Decodes groups of five bits.  Returns `None` if the number of bits
+    /// is not a multiple of 5, or if some group is not a valid code.  The
+    /// classic alphabet decodes to I and U rather than J and V.
+    ///
+    /// The bits can come from any classification of symbols.  For
+    /// example, to read a hidden message with capital letters as B:
+    /// ```
+    /// use puzzletools::code::binary::Bacon;
+    /// let text = "buRNT tOast";
+    /// let bits: Vec<bool> = text
+    ///     .chars()
+    ///     .filter(char::is_ascii_alphabetic)
+    ///     .map(|c| c.is_ascii_uppercase())
+    ///     .collect();
+    /// assert_eq!(Bacon::Classic.decode(&bits), Some("HI".to_string()));
+    /// ```
+    pub fn decode(self, bits: &[bool]) -> Option<String> {
+        if !bits.len().is_multiple_of(5) {
+            return None;
+        }
+        bits.chunks(5)
+            .map(|b| bits_to_number(b.iter().copied()).and_then(|n| self.letter(n)))
+            .collect()
+    }
+}
+
+const FIGURES_SHIFT: u8 = 0b11011;
+const LETTERS_SHIFT: u8 = 0b11111;
+
+// The ITA2 characters in letters and figures modes, indexed by code.
+// Codes with no character are marked with '\0'.
+const ITA2_LETTERS: [char; 32] = [
+    '\0', 'T', '\n', 'O', ' ', 'H', 'N', 'M', '\r', 'L', 'R', 'G', 'I', 'P', 'C', 'V', 'E', 'Z',
+    'D', 'B', 'S', 'Y', 'F', 'X', 'A', 'W', 'J', '\0', 'U', 'Q', 'K', '\0',
+];
+const ITA2_FIGURES: [char; 32] = [
+    '\0', '5', '\n', '9', ' ', '\0', ',', '.', '\r', ')', '4', '\0', '8', '0', ':', '=', '3', '+',
+    '\u{5}', '?', '\'', '6', '\0', '/', '-', '2', '\u{7}', '\0', '7', '1', '(', '\0',
+];
+
+/// Decodes a sequence of 5-bit ITA2 (Baudot-Murray) codes, starting in
+/// letters mode.  Codes are numbered with the first bit as the most
+/// significant, so that A is 11000.  The figures shift (11011) and
+/// letters shift (11111) codes switch modes; unassigned codes are
+/// skipped.
+/// ```
+/// use puzzletools::code::binary::{decode_ita2, parse_bits};
+/// let bits = parse_bits("00001 00101 10000 00100 11011 01010 10000", '0', '1');
+/// let codes = bits.chunks(5).map(|b| b.iter().fold(0, |n, &b| 2 * n + b as u8));
+/// assert_eq!(decode_ita2(codes), "THE 43");
+/// ```
+pub fn decode_ita2<I: IntoIterator<Item = u8>>(codes: I) -> String {
+    let mut figures = false;
+    let mut s = String::new();
+    for code in codes {
+        match code & 0x1f {
+            FIGURES_SHIFT => figures = true,
+            LETTERS_SHIFT => figures = false,
+            c => {
+                let table = if figures {
+                    &ITA2_FIGURES
+                } else {
+                    &ITA2_LETTERS
+                };
+                if table[c as usize] != '\0' {
+                    s.push(table[c as usize]);
+                }
+            }
+        }
+    }
+    s
+}
+
+/// Encodes a string as ITA2 codes, inserting shifts as needed (starting
+/// in letters mode).  Returns `None` if some character cannot be encoded.
+/// ```
+/// use puzzletools::code::binary::{decode_ita2, encode_ita2};
+/// let codes = encode_ita2("Route 66").unwrap();
+/// assert_eq!(codes.len(), 9);
+/// assert_eq!(decode_ita2(codes), "ROUTE 66");
+/// ```
+pub fn encode_ita2<S: Text>(s: S) -> Option<Vec<u8>> {
+    let mut figures = false;
+    let mut v = Vec::new();
+    for c in s.chars().map(|c| c.to_ascii_uppercase()) {
+        let find = |table: &[char; 32]| table.iter().position(|&d| d == c && c != '\0');
+        // characters in both modes (space, CR, LF) don't need a shift
+        let code = match (find(&ITA2_LETTERS), find(&ITA2_FIGURES)) {
+            (Some(l), Some(f)) if l == f => l,
+            (Some(l), _) => {
+                if figures {
+                    v.push(LETTERS_SHIFT);
+                    figures = false;
+                }
+                l
+            }
+            (None, Some(f)) => {
+                if !figures {
+                    v.push(FIGURES_SHIFT);
+                    figures = true;
+                }
+                f
+            }
+            (None, None) => return None,
+        };
+        v.push(code as u8);
+    }
+    Some(v)
+}
+
+#[test]
+fn ita2_letters_test() {
+    let codes = [
+        "11000", "10011", "01110", "10010", "10000", "10110", "01011", "00101", "01100", "11010",
+        "11110", "01001", "00111", "00110", "00011", "01101", "11101", "01010", "10100", "00001",
+        "11100", "01111", "11001", "10111", "10101", "10001",
+    ];
+    for (c, code) in ('A'..='Z').zip(codes) {
+        let n = bits_to_number(parse_bits(code, '0', '1')).unwrap() as u8;
+        assert_eq!(decode_ita2([n]), c.to_string());
+        assert_eq!(encode_ita2(c.to_string()), Some(vec![n]));
+    }
+    let figures = [
+        (0b10011, '?'),
+        (0b11001, '2'),
+        (0b10111, '/'),
+        (0b11101, '1'),
+    ];
+    for (n, c) in figures {
+        assert_eq!(decode_ita2([FIGURES_SHIFT, n]), c.to_string());
+    }
+}
+
+#[test]
+fn wide_ascii_test() {
+    assert_eq!(encode_ascii("A", 70).len(), 70);
+    assert!(number_to_bits(1, 70)[69]);
+}
@@ -0,0 +1,337 @@
+//! The genetic code, with whole-sequence translation and alternate
+//! codon tables.
+//!
+//! Stop codons translate to `#`, as in `dna_letter` and `rna_letter`.
+//!
+//! To find words whose DNA reading in some frame spells another word:
+//! ```
+//! use std::io::Cursor;
+//! use puzzletools::code::genetic::STANDARD;
+//! use puzzletools::letter::is_dna_letter;
+//! use puzzletools::word::Text;
+//! use puzzletools::wordlist::{Wordlist, pairs_iter};
+//! let wl = Wordlist::load_from_reader(Cursor::new("GATTACA,1\nIT,1")).unwrap();
+//! let dna = wl.iter().filter(|w| w.slug.bytes().all(is_dna_letter));
+//! let v: Vec<_> = pairs_iter(dna, &wl, |w| STANDARD.six_frames(&w.slug).into_iter().flatten())
+//!     .map(|(w1, w2)| (w1.word.clone(), w2.word.clone()))
+//!     .collect();
+//! assert_eq!(v, vec![("GATTACA".to_string(), "IT".to_string())]);
+//! ```
+
+use crate::word::Text;
+
+/// The character used for stop codons.
+pub const STOP: char = '#';
+
+const BASES: [u8; 4] = [b'T', b'C', b'A', b'G'];
+
+/// A table assigning amino acids to codons.
+pub struct CodonTable {
+    /// The NCBI translation table number.
+    pub id: u8,
+    pub name: &'static str,
+    // amino acids in NCBI order (TTT, TTC, TTA, TTG, TCT, ...)
+    amino_acids: &'static [u8; 64],
+}
+
+pub static STANDARD: CodonTable = CodonTable {
+    id: 1,
+    name: "Standard",
+    amino_acids: b"FFLLSSSSYY##CC#WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+};
+
+pub static VERTEBRATE_MITOCHONDRIAL: CodonTable = CodonTable {
+    id: 2,
+    name: "Vertebrate Mitochondrial",
+    amino_acids: b"FFLLSSSSYY##CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS##VVVVAAAADDEEGGGG",
+};
+
+pub static YEAST_MITOCHONDRIAL: CodonTable = CodonTable {
+    id: 3,
+    name: "Yeast Mitochondrial",
+    amino_acids: b"FFLLSSSSYY##CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+};
+
+pub static MOLD_MITOCHONDRIAL: CodonTable = CodonTable {
+    id: 4,
+    name: "Mold, Protozoan, and Coelenterate Mitochondrial",
+    amino_acids: b"FFLLSSSSYY##CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+};
+
+pub static INVERTEBRATE_MITOCHONDRIAL: CodonTable = CodonTable {
+    id: 5,
+    name: "Invertebrate Mitochondrial",
+    amino_acids: b"FFLLSSSSYY##CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+};
+
+pub static CILIATE_NUCLEAR: CodonTable = CodonTable {
+    id: 6,
+    name: "Ciliate, Dasycladacean and Hexamita Nuclear",
+    amino_acids: b"FFLLSSSSYYQQCC#WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+};
+
+/// All of the codon tables defined in this module.
+pub static CODON_TABLES: [&CodonTable; 6] = [
+    &STANDARD,
+    &VERTEBRATE_MITOCHONDRIAL,
+    &YEAST_MITOCHONDRIAL,
+    &MOLD_MITOCHONDRIAL,
+    &INVERTEBRATE_MITOCHONDRIAL,
+    &CILIATE_NUCLEAR,
+];
+
+const AMINO_ACIDS: [(char, &str, &str); 23] = [
+    ('A', "Ala", "Alanine"),
+    ('R', "Arg", "Arginine"),
+    ('N', "Asn", "Asparagine"),
+    ('D', "Asp", "Aspartic acid"),
+    ('C', "Cys", "Cysteine"),
+    ('Q', "Gln", "Glutamine"),
+    ('E', "Glu", "Glutamic acid"),
+    ('G', "Gly", "Glycine"),
+    ('H', "His", "Histidine"),
+    ('I', "Ile", "Isoleucine"),
+    ('L', "Leu", "Leucine"),
+    ('K', "Lys", "Lysine"),
+    ('M', "Met", "Methionine"),
+    ('F', "Phe", "Phenylalanine"),
+    ('P', "Pro", "Proline"),
+    ('S', "Ser", "Serine"),
+    ('T', "Thr", "Threonine"),
+    ('W', "Trp", "Tryptophan"),
+    ('Y', "Tyr", "Tyrosine"),
+    ('V', "Val", "Valine"),
+    ('U', "Sec", "Selenocysteine"),
+    ('O', "Pyl", "Pyrrolysine"),
+    (STOP, "Ter", "Stop"),
+];
+
+fn base_index(c: u8) -> Option<usize> {
+    match c {
+        b'T' | b'U' => Some(0),
+        b'C' => Some(1),
+        b'A' => Some(2),
+        b'G' => Some(3),
+        _ => None,
+    }
+}
+
+fn codon_index(codon: &[u8]) -> Option<usize> {
+    match codon {
+        [a, b, c] => Some(16 * base_index(*a)? + 4 * base_index(*b)? + base_index(*c)?),
+        _ => None,
+    }
+}
+
+fn codon_string(idx: usize) -> String {
+    [idx / 16, (idx / 4) % 4, idx % 4]
+        .iter()
+        .map(|&n| BASES[n] as char)
+        .collect()
+}
+
+impl CodonTable {
+    /// Returns the amino acid for a three-letter DNA or RNA sequence.
+    /// ```
+    /// use puzzletools::code::genetic::{STANDARD, VERTEBRATE_MITOCHONDRIAL};
+    /// assert_eq!(STANDARD.translate_codon("TGA"), Some('#'));
+    /// assert_eq!(VERTEBRATE_MITOCHONDRIAL.translate_codon("UGA"), Some('W'));
+    /// assert_eq!(STANDARD.translate_codon("TG"), None);
+    /// ```
+    pub fn translate_codon<S: Text>(&self, codon: S) -> Option<char> {
+        codon_index(codon.as_bytes()).map(|n| self.amino_acids[n] as char)
+    }
+
+    /// Translates a DNA or RNA sequence, starting from the first letter.
+    /// Stop codons become `#`, and a partial codon at the end is ignored.
+    /// Returns `None` if the sequence contains a letter other than A, C,
+    /// G, T or U.
+    /// ```
+    /// use puzzletools::code::genetic::STANDARD;
+    /// assert_eq!(STANDARD.translate("TGGTAAATTTA").as_deref(), Some("W#I"));
+    /// assert_eq!(STANDARD.translate("BAD"), None);
+    /// ```
+    pub fn translate<S: Text>(&self, s: S) -> Option<String> {
+        s.as_bytes()
+            .chunks_exact(3)
+            .map(|c| codon_index(c).map(|n| self.amino_acids[n] as char))
+            .collect()
+    }
+
+    /// Translates a DNA or RNA sequence up to (but not including) the
+    /// first stop codon.
+    /// ```
+    /// use puzzletools::code::genetic::STANDARD;
+    /// assert_eq!(STANDARD.translate_to_stop("ATGTGGTAGATG").as_deref(), Some("MW"));
+    /// ```
+    pub fn translate_to_stop<S: Text>(&self, s: S) -> Option<String> {
+        let mut v = String::new();
+        for c in s.as_bytes().chunks_exact(3) {
+            let aa = self.amino_acids[codon_index(c)?] as char;
+            if aa == STOP {
+                break;
+            }
+            v.push(aa);
+        }
+        Some(v)
+    }
+
+    /// Translates a sequence in all six reading frames: the three frames
+    /// of the sequence itself, followed by the three frames of its
+    /// reverse complement.
+    /// ```
+    /// use puzzletools::code::genetic::STANDARD;
+    /// let frames = STANDARD.six_frames("CATTAG").unwrap();
+    /// assert_eq!(frames, vec!["H#", "I", "L", "LM", "#", "N"]);
+    /// ```
+    pub fn six_frames<S: Text>(&self, s: S) -> Option<Vec<String>> {
+        let rc = reverse_complement(s.as_str())?;
+        let bytes = s.as_bytes();
+        (0..3)
+            .map(|n| self.translate(bytes.get(n..).unwrap_or_default()))
+            .chain((0..3).map(|n| self.translate(rc.as_bytes().get(n..).unwrap_or_default())))
+            .collect()
+    }
+
+    /// Returns the DNA codons that translate to the given amino acid.
+    /// ```
+    /// use puzzletools::code::genetic::{STANDARD, VERTEBRATE_MITOCHONDRIAL};
+    /// assert_eq!(STANDARD.codons('W'), vec!["TGG"]);
+    /// assert_eq!(VERTEBRATE_MITOCHONDRIAL.codons('W'), vec!["TGA", "TGG"]);
+    /// ```
+    pub fn codons(&self, amino_acid: char) -> Vec<String> {
+        let aa = amino_acid.to_ascii_uppercase() as u8;
+        (0..64)
+            .filter(|&n| self.amino_acids[n] == aa)
+            .map(codon_string)
+            .collect()
+    }
+
+    /// Returns, for each amino acid in the string, the list of DNA codons
+    /// that translate to it.  Returns `None` if some letter is not
+    /// produced by any codon.
+    /// ```
+    /// use puzzletools::code::genetic::STANDARD;
+    /// let v = STANDARD.reverse_translate("MY").unwrap();
+    /// assert_eq!(v, vec![vec!["ATG"], vec!["TAT", "TAC"]]);
+    /// assert_eq!(STANDARD.reverse_translate("BOX"), None);
+    /// ```
+    pub fn reverse_translate<S: Text>(&self, s: S) -> Option<Vec<Vec<String>>> {
+        s.chars()
+            .map(|c| Some(self.codons(c)).filter(|v| !v.is_empty()))
+            .collect()
+    }
+
+    /// Returns the number of DNA sequences that translate to the given
+    /// string, or `None` if the number does not fit in a `u128` (which
+    /// can happen for strings of about 50 or more amino acids).
+    /// ```
+    /// use puzzletools::code::genetic::STANDARD;
+    /// assert_eq!(STANDARD.num_reverse_translations("MY"), Some(2));
+    /// assert_eq!(STANDARD.num_reverse_translations("BOX"), Some(0));
+    /// assert_eq!(STANDARD.num_reverse_translations("L".repeat(50)), None);
+    /// ```
+    pub fn num_reverse_translations<S: Text>(&self, s: S) -> Option<u128> {
+        let counts: Vec<u128> = s
+            .chars()
+            .map(|c| {
+                let aa = c.to_ascii_uppercase() as u8;
+                self.amino_acids.iter().filter(|&&a| a == aa).count() as u128
+            })
+            .collect();
+        if counts.contains(&0) {
+            return Some(0);
+        }
+        counts.into_iter().try_fold(1u128, u128::checked_mul)
+    }
+}
+
+/// Returns the codon table with the given NCBI table number.
+/// ```
+/// use puzzletools::code::genetic::codon_table;
+/// assert_eq!(codon_table(2).unwrap().name, "Vertebrate Mitochondrial");
+/// assert!(codon_table(11).is_some());
+/// assert!(codon_table(99).is_none());
+/// ```
+pub fn codon_table(id: u8) -> Option<&'static CodonTable> {
+    // table 11 (bacterial, archaeal and plant plastid) only differs from
+    // the standard table in its start codons
+    let id = if id == 11 { 1 } else { id };
+    CODON_TABLES.iter().copied().find(|t| t.id == id)
+}
+
+/// Returns the reverse complement of a DNA or RNA sequence.  If the
+/// sequence contains a U, the result is an RNA sequence; otherwise it
+/// is a DNA sequence.  Returns `None` if the sequence contains a letter
+/// other than A, C, G, T or U.
+/// ```
+/// use puzzletools::code::genetic::reverse_complement;
+/// assert_eq!(reverse_complement("GATTACA").as_deref(), Some("TGTAATC"));
+/// assert_eq!(reverse_complement("GAUUACA").as_deref(), Some("UGUAAUC"));
+/// ```
+pub fn reverse_complement<S: Text>(s: S) -> Option<String> {
+    let a_comp = if s.bytes().any(|c| c == b'U') {
+        'U'
+    } else {
+        'T'
+    };
+    s.bytes()
+        .rev()
+        .map(|c| match c {
+            b'A' => Some(a_comp),
+            b'C' => Some('G'),
+            b'G' => Some('C'),
+            b'T' | b'U' => Some('A'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the three-letter abbreviation of an amino acid.
+/// ```
+/// use puzzletools::code::genetic::amino_acid_abbreviation;
+/// assert_eq!(amino_acid_abbreviation('W'), Some("Trp"));
+/// assert_eq!(amino_acid_abbreviation('#'), Some("Ter"));
+/// ```
+pub fn amino_acid_abbreviation(c: char) -> Option<&'static str> {
+    let c = c.to_ascii_uppercase();
+    AMINO_ACIDS.iter().find(|a| a.0 == c).map(|a| a.1)
+}
+
+/// Returns the full name of an amino acid.
+/// ```
+/// use puzzletools::code::genetic::amino_acid_name;
+/// assert_eq!(amino_acid_name('K'), Some("Lysine"));
+/// assert_eq!(amino_acid_name('B'), None);
+/// ```
+pub fn amino_acid_name(c: char) -> Option<&'static str> {
+    let c = c.to_ascii_uppercase();
+    AMINO_ACIDS.iter().find(|a| a.0 == c).map(|a| a.2)
+}
+
+/// Returns the one-letter code of the amino acid with the given
+/// three-letter abbreviation or full name (ignoring case).
+/// ```
+/// use puzzletools::code::genetic::amino_acid_letter;
+/// assert_eq!(amino_acid_letter("TRP"), Some('W'));
+/// assert_eq!(amino_acid_letter("glutamine"), Some('Q'));
+/// ```
+pub fn amino_acid_letter(name: &str) -> Option<char> {
+    AMINO_ACIDS
+        .iter()
+        .find(|a| a.1.eq_ignore_ascii_case(name) || a.2.eq_ignore_ascii_case(name))
+        .map(|a| a.0)
+}
+
+#[test]
+fn standard_table_test() {
+    for n in 0..64 {
+        let codon = codon_string(n);
+        assert_eq!(
+            STANDARD.translate_codon(&codon),
+            super::dna_letter(&codon),
+            "{}",
+            codon
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Phone keypad encodings: T9 digits, multi-tap, and vanity numbers.
+
+use crate::letter::Letter;
+use crate::search::{sort_results, SearchResult};
+use crate::word::Text;
+use crate::wordlist::{Wordlist, WordlistEntry, WordlistIndex};
+
+/// The letters on each key of a phone keypad, indexed by digit.
+pub const KEYPAD: [&str; 10] = [
+    "", "", "ABC", "DEF", "GHI", "JKL", "MNO", "PQRS", "TUV", "WXYZ",
+];
+
+/// Returns the digit of the key with the given letter.
+/// ```
+/// use puzzletools::code::keypad::key_digit;
+/// assert_eq!(key_digit('s'), Some('7'));
+/// assert_eq!(key_digit('!'), None);
+/// ```
+pub fn key_digit<L: Letter>(c: L) -> Option<char> {
+    let c = c.byte().to_ascii_uppercase();
+    KEYPAD
+        .iter()
+        .position(|k| k.as_bytes().contains(&c))
+        .map(|d| (b'0' + d as u8) as char)
+}
+
+/// Returns the letter typed by pressing the key `digit` the given number
+/// of times.
+/// ```
+/// use puzzletools::code::keypad::keypad_letter;
+/// assert_eq!(keypad_letter('7', 4), Some('S'));
+/// assert_eq!(keypad_letter('2', 4), None);
+/// ```
+pub fn keypad_letter(digit: char, presses: usize) -> Option<char> {
+    let key = KEYPAD[digit.to_digit(10)? as usize];
+    key.chars().nth(presses.checked_sub(1)?)
+}
+
+/// Converts the letters of `s` to keypad digits.  Digits are kept, and
+/// everything else is skipped.
+/// ```
+/// use puzzletools::code::keypad::to_digits;
+/// assert_eq!(to_digits("1-800-FLOWERS"), "18003569377");
+/// ```
+pub fn to_digits<S: Text>(s: S) -> String {
+    s.chars()
+        .filter_map(|c| {
+            if c.is_ascii_digit() {
+                Some(c)
+            } else if c.is_ascii_alphabetic() {
+                key_digit(c)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Encodes the letters of `s` with multi-tap, where each letter is
+/// written as its key repeated once for each press.  Letters are
+/// separated by spaces.
+/// ```
+/// use puzzletools::code::keypad::multi_tap_encode;
+/// assert_eq!(multi_tap_encode("Hello"), "44 33 555 555 666");
+/// ```
+pub fn multi_tap_encode<S: Text>(s: S) -> String {
+    let v: Vec<String> = s
+        .chars()
+        .filter_map(|c| {
+            let d = key_digit(c)?;
+            let key = KEYPAD[d.to_digit(10).unwrap() as usize];
+            let presses = key.find(c.to_ascii_uppercase()).unwrap() + 1;
+            Some(d.to_string().repeat(presses))
+        })
+        .collect();
+    v.join(" ")
+}
+
+/// Decodes multi-tap.  Each run of the same digit is a letter, and runs
+/// of the same digit can be separated by any non-digit character.
+/// Returns `None` if some run does not correspond to a letter.
+/// ```
+/// use puzzletools::code::keypad::multi_tap_decode;
+/// assert_eq!(multi_tap_decode("4433555 555666"), Some("HELLO".to_string()));
+/// assert_eq!(multi_tap_decode("22222"), None);
+/// ```
+pub fn multi_tap_decode<S: Text>(s: S) -> Option<String> {
+    let mut v = String::new();
+    let mut run: Option<(char, usize)> = None;
+    for c in s.chars().chain(std::iter::once(' ')) {
+        match run {
+            Some((d, n)) if d == c => run = Some((d, n + 1)),
+            _ => {
+                if let Some((d, n)) = run {
+                    v.push(keypad_letter(d, n)?);
+                }
+                run = if c.is_ascii_digit() {
+                    Some((c, 1))
+                } else {
+                    None
+                };
+            }
+        }
+    }
+    Some(v)
+}
+
+/// Builds an index of the entries of a wordlist by their keypad digits,
+/// for T9-style lookups.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::code::keypad::t9_index;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("GOOD,10\nHOME,8\nGONE,5\nHOOD,2")).unwrap();
+/// let index = t9_index(&wl);
+/// let words: Vec<_> = index.get("4663").iter().map(|e| e.word.as_str()).collect();
+/// assert_eq!(words, vec!["GOOD", "HOME", "GONE", "HOOD"]);
+/// ```
+pub fn t9_index(wl: &Wordlist) -> WordlistIndex<'_, String> {
+    wl.index_by(|e| Some(to_digits(&e.slug)))
+}
+
+/// A way of writing a phone number as a sequence of words.
+#[derive(Clone, Debug)]
+pub struct VanityPhrase<'a> {
+    pub words: Vec<&'a WordlistEntry>,
+}
+
+impl SearchResult for VanityPhrase<'_> {
+    type Data = String;
+    type Freq = u64;
+    fn data(&self) -> String {
+        let words: Vec<&str> = self.words.iter().map(|w| w.word.as_str()).collect();
+        words.join(" ")
+    }
+    /// The frequency of the least common word.
+    fn freq(&self) -> u64 {
+        self.words.iter().map(|w| w.freq).min().unwrap_or(0)
+    }
+}
+
+/// Finds the ways to write the digits of a phone number as a sequence of
+/// words (from an index built by `t9_index`) with at least `min_len`
+/// letters each, ordered from best to worst.  Characters other than
+/// digits are ignored.  Since 0 and 1 have no letters, leave them out
+/// (for example, the `1-800` of a toll-free number).
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::code::keypad::{t9_index, vanity_phrases};
+/// use puzzletools::search::SearchResult;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("FLOWERS,10\nFLOW,20\nERS,1\nDRS,5")).unwrap();
+/// let index = t9_index(&wl);
+/// let v: Vec<_> = vanity_phrases("356-9377", &index, 3).map(|p| p.data()).collect();
+/// assert_eq!(v, vec!["FLOWERS", "FLOW DRS", "FLOW ERS"]);
+/// ```
+pub fn vanity_phrases<'a, S: Text>(
+    number: S,
+    index: &WordlistIndex<'a, String>,
+    min_len: usize,
+) -> impl Iterator<Item = VanityPhrase<'a>> {
+    let digits: String = number.chars().filter(char::is_ascii_digit).collect();
+    let mut results = Vec::new();
+    if !digits.is_empty() {
+        extend_phrase(
+            &digits,
+            index,
+            min_len.max(1),
+            &mut Vec::new(),
+            &mut results,
+        );
+    }
+    sort_results(results.into_iter())
+}
+
+fn extend_phrase<'a>(
+    digits: &str,
+    index: &WordlistIndex<'a, String>,
+    min_len: usize,
+    words: &mut Vec<&'a WordlistEntry>,
+    results: &mut Vec<VanityPhrase<'a>>,
+) {
+    if digits.is_empty() {
+        results.push(VanityPhrase {
+            words: words.clone(),
+        });
+        return;
+    }
+    for len in min_len..=digits.len() {
+        for &w in index.get(&digits[..len]) {
+            words.push(w);
+            extend_phrase(&digits[len..], index, min_len, words, results);
+            words.pop();
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Seven- and fourteen-segment displays, and calculator spelling.
+
+use crate::letter::Letter;
+use crate::word::Text;
+use crate::wordlist::{Wordlist, WordlistEntry};
+
+// Bit 0 is the top segment, and bits 1 through 5 go clockwise around the
+// digit, ending at the upper left.  Bit 6 is the middle segment.
+const SEVEN_DIGITS: [u16; 10] = [0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F];
+
+// The usual seven-segment forms of the letters, which mix capital and
+// lowercase shapes (b, d, n, q, r, t, y).  K, M, V, W, X and Z cannot
+// be displayed.
+const SEVEN_LETTERS: [u16; 26] = [
+    0x77, 0x7C, 0x39, 0x5E, 0x79, 0x71, 0x3D, 0x76, 0x30, 0x1E, 0, 0x38, 0, 0x54, 0x3F, 0x73, 0x67,
+    0x50, 0x6D, 0x78, 0x3E, 0, 0, 0, 0x6E, 0,
+];
+
+// The seven segments are as above, with the middle segment split into
+// two (bits 6 and 7), then the upper left diagonal, upper vertical, upper
+// right diagonal, lower left diagonal, lower vertical and lower right
+// diagonal (bits 8 through 13).
+const FOURTEEN_DIGITS: [u16; 10] = [
+    0x0C3F, 0x0006, 0x00DB, 0x008F, 0x00E6, 0x2069, 0x00FD, 0x0007, 0x00FF, 0x00EF,
+];
+
+const FOURTEEN_LETTERS: [u16; 26] = [
+    0x00F7, 0x128F, 0x0039, 0x120F, 0x00F9, 0x0071, 0x00BD, 0x00F6, 0x1209, 0x001E, 0x2470, 0x0038,
+    0x0536, 0x2136, 0x003F, 0x00F3, 0x203F, 0x20F3, 0x018D, 0x1201, 0x003E, 0x0C30, 0x2836, 0x2D00,
+    0x1500, 0x0C09,
+];
+
+/// A kind of segment display.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SegmentDisplay {
+    /// A seven-segment display, as on a calculator or digital clock.
+    Seven,
+    /// A fourteen-segment display, which has diagonal and central
+    /// segments so that it can show every letter.
+    Fourteen,
+}
+
+impl SegmentDisplay {
+    /// Returns the segments that are lit to display `c`, as a bitmask, or
+    /// `None` if `c` cannot be displayed.  Letters can be either case.
+    /// ```
+    /// use puzzletools::code::segment::SegmentDisplay;
+    /// assert_eq!(SegmentDisplay::Seven.segments('8'), Some(0x7F));
+    /// assert_eq!(SegmentDisplay::Seven.segments('M'), None);
+    /// assert!(SegmentDisplay::Fourteen.segments('M').is_some());
+    /// ```
+    pub fn segments<L: Letter>(&self, c: L) -> Option<u16> {
+        let (digits, letters) = match self {
+            SegmentDisplay::Seven => (&SEVEN_DIGITS, &SEVEN_LETTERS),
+            SegmentDisplay::Fourteen => (&FOURTEEN_DIGITS, &FOURTEEN_LETTERS),
+        };
+        let c = c.byte().to_ascii_uppercase();
+        let bits = match c {
+            b'0'..=b'9' => digits[(c - b'0') as usize],
+            b'A'..=b'Z' => letters[(c - b'A') as usize],
+            _ => 0,
+        };
+        if bits == 0 {
+            None
+        } else {
+            Some(bits)
+        }
+    }
+
+    /// Returns `true` if every letter and digit of `s` can be displayed.
+    /// Other characters are ignored.
+    /// ```
+    /// use puzzletools::code::segment::SegmentDisplay;
+    /// assert!(SegmentDisplay::Seven.can_display("HELLO"));
+    /// assert!(!SegmentDisplay::Seven.can_display("WORLD"));
+    /// assert!(SegmentDisplay::Fourteen.can_display("WORLD"));
+    /// ```
+    pub fn can_display<S: Text>(&self, s: S) -> bool {
+        s.bytes()
+            .filter(u8::is_ascii_alphanumeric)
+            .all(|c| self.segments(c).is_some())
+    }
+
+    /// Returns the total number of segments lit to display the letters and
+    /// digits of `s`, or `None` if some character cannot be displayed.
+    /// ```
+    /// use puzzletools::code::segment::SegmentDisplay;
+    /// assert_eq!(SegmentDisplay::Seven.segment_count("1"), Some(2));
+    /// assert_eq!(SegmentDisplay::Seven.segment_count("HI"), Some(7));
+    /// ```
+    pub fn segment_count<S: Text>(&self, s: S) -> Option<u32> {
+        s.bytes()
+            .filter(u8::is_ascii_alphanumeric)
+            .map(|c| self.segments(c).map(u16::count_ones))
+            .sum()
+    }
+
+    /// Returns the number of segments that must be turned on or off to
+    /// change the display of one character into the other, or `None` if
+    /// one of them cannot be displayed.
+    /// ```
+    /// use puzzletools::code::segment::SegmentDisplay;
+    /// assert_eq!(SegmentDisplay::Seven.distance('8', '0'), Some(1));
+    /// assert_eq!(SegmentDisplay::Seven.distance('E', 'F'), Some(1));
+    /// ```
+    pub fn distance<L: Letter, M: Letter>(&self, c1: L, c2: M) -> Option<u32> {
+        Some((self.segments(c1)? ^ self.segments(c2)?).count_ones())
+    }
+
+    /// Returns the entries of a wordlist that can be displayed.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::code::segment::SegmentDisplay;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("HELLO,5\nWORLD,4\nPUZZLE,3\nCAFE,2")).unwrap();
+    /// let v: Vec<_> = SegmentDisplay::Seven.displayable_words(&wl).map(|e| e.word.as_str()).collect();
+    /// assert_eq!(v, vec!["HELLO", "CAFE"]);
+    /// ```
+    pub fn displayable_words<'a>(
+        &self,
+        wl: &'a Wordlist,
+    ) -> impl Iterator<Item = &'a WordlistEntry> + use<'a> {
+        let display = *self;
+        wl.iter().filter(move |e| display.can_display(&e.slug))
+    }
+}
+
+// The letters that the digits look like when a calculator is turned
+// upside down.
+const CALCULATOR: [(u8, u8); 9] = [
+    (b'0', b'O'),
+    (b'1', b'I'),
+    (b'2', b'Z'),
+    (b'3', b'E'),
+    (b'4', b'H'),
+    (b'5', b'S'),
+    (b'6', b'G'),
+    (b'7', b'L'),
+    (b'8', b'B'),
+];
+
+/// Reads a number on an upside-down calculator (0 is O, 1 is I, 2 is Z,
+/// 3 is E, 4 is H, 5 is S, 6 is G, 7 is L and 8 is B).  Characters other
+/// than digits are ignored.  Returns `None` if some digit does not look
+/// like a letter.
+/// ```
+/// use puzzletools::code::segment::calculator_word;
+/// assert_eq!(calculator_word("0.7734"), Some("HELLO".to_string()));
+/// assert_eq!(calculator_word("38076"), Some("GLOBE".to_string()));
+/// assert_eq!(calculator_word("99"), None);
+/// ```
+pub fn calculator_word<S: Text>(number: S) -> Option<String> {
+    number
+        .bytes()
+        .rev()
+        .filter(u8::is_ascii_digit)
+        .map(|c| CALCULATOR.iter().find(|p| p.0 == c).map(|p| p.1 as char))
+        .collect()
+}
+
+/// Returns the number that spells `word` on an upside-down calculator
+/// (see `calculator_word`), or `None` if some letter cannot be spelled.
+/// Characters other than letters are ignored.
+/// ```
+/// use puzzletools::code::segment::calculator_number;
+/// assert_eq!(calculator_number("Hello"), Some("07734".to_string()));
+/// assert_eq!(calculator_number("WORLD"), None);
+/// ```
+pub fn calculator_number<S: Text>(word: S) -> Option<String> {
+    word.bytes()
+        .rev()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|c| {
+            let c = c.to_ascii_uppercase();
+            CALCULATOR.iter().find(|p| p.1 == c).map(|p| p.0 as char)
+        })
+        .collect()
+}
@@ -0,0 +1,302 @@
+//! Flag semaphore.
+//!
+//! An arm position is a number from 0 to 7, measured in eighths of a
+//! circle clockwise from straight down, as seen by someone facing the
+//! signaller.  So 0 is down, 2 is to the observer's left, 4 is up and
+//! 6 is to the observer's right.  A signal is a pair of arm positions,
+//! stored with the smaller position first.
+
+use crate::letter::{lett_to_num_0, Letter};
+use crate::word::Text;
+
+/// A pair of arm positions, with the smaller position first.
+pub type Signal = (u8, u8);
+
+const LETTERS: [Signal; 26] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (0, 5),
+    (0, 6),
+    (0, 7),
+    (1, 2),
+    (1, 3),
+    (4, 6),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+    (1, 7),
+    (2, 3),
+    (2, 4),
+    (2, 5),
+    (2, 6),
+    (2, 7),
+    (3, 4),
+    (3, 5),
+    (4, 7),
+    (5, 6),
+    (5, 7),
+    (3, 6),
+    (6, 7),
+];
+
+/// Both arms down, used for spaces.
+pub const REST: Signal = (0, 0);
+
+/// The signal that switches to numerals mode.
+pub const NUMERALS: Signal = (4, 5);
+
+/// The signal that switches back to letters mode.  This is the same as J.
+pub const LETTERS_SIGN: Signal = (4, 6);
+
+/// The signal that cancels the previous signal.
+pub const CANCEL: Signal = (3, 7);
+
+/// Puts the two arm positions of a signal in order, reducing them modulo 8.
+/// ```
+/// use puzzletools::code::semaphore::signal;
+/// assert_eq!(signal(3, 1), (1, 3));
+/// assert_eq!(signal(9, 0), (0, 1));
+/// ```
+pub fn signal(a: u8, b: u8) -> Signal {
+    let (a, b) = (a % 8, b % 8);
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Returns the semaphore signal for the given letter or digit.  Digits
+/// are sent using the signals for the letters A through I (for 1 through
+/// 9) and K (for 0).
+/// ```
+/// use puzzletools::code::semaphore::to_semaphore;
+/// assert_eq!(to_semaphore('R'), Some((2, 6)));
+/// assert_eq!(to_semaphore('r'), Some((2, 6)));
+/// assert_eq!(to_semaphore('3'), to_semaphore('C'));
+/// assert_eq!(to_semaphore('!'), None);
+/// ```
+pub fn to_semaphore<L: Letter>(c: L) -> Option<Signal> {
+    match c.byte().to_ascii_uppercase() {
+        c @ b'A'..=b'Z' => Some(LETTERS[lett_to_num_0(c)]),
+        b'0' => Some(LETTERS[lett_to_num_0(b'K')]),
+        c @ b'1'..=b'9' => Some(LETTERS[(c - b'1') as usize]),
+        _ => None,
+    }
+}
+
+/// Returns the letter sent by the given pair of arm positions, which
+/// may be given in either order.
+/// ```
+/// use puzzletools::code::semaphore::from_semaphore;
+/// assert_eq!(from_semaphore(6, 2), Some('R'));
+/// assert_eq!(from_semaphore(0, 0), None);
+/// ```
+pub fn from_semaphore(a: u8, b: u8) -> Option<char> {
+    let s = signal(a, b);
+    LETTERS
+        .iter()
+        .position(|&l| l == s)
+        .map(|n| (b'A' + n as u8) as char)
+}
+
+/// Returns the digit sent by the given pair of arm positions in
+/// numerals mode.
+/// ```
+/// use puzzletools::code::semaphore::from_semaphore_numeral;
+/// assert_eq!(from_semaphore_numeral(0, 3), Some('3'));
+/// assert_eq!(from_semaphore_numeral(1, 4), Some('0'));
+/// assert_eq!(from_semaphore_numeral(2, 6), None);
+/// ```
+pub fn from_semaphore_numeral(a: u8, b: u8) -> Option<char> {
+    match from_semaphore(a, b)? {
+        'K' => Some('0'),
+        c @ 'A'..='I' => Some((c as u8 - b'A' + b'1') as char),
+        _ => None,
+    }
+}
+
+/// Encodes a string as a sequence of signals.  Spaces become `REST`, and
+/// the `NUMERALS` and `LETTERS_SIGN` signals are inserted when switching
+/// between letters and digits.  Other characters are skipped.
+/// ```
+/// use puzzletools::code::semaphore::{encode, NUMERALS, LETTERS_SIGN};
+/// assert_eq!(encode("B2B"), vec![(0, 2), NUMERALS, (0, 2), LETTERS_SIGN, (0, 2)]);
+/// ```
+pub fn encode<S: Text>(s: S) -> Vec<Signal> {
+    let mut v = Vec::with_capacity(s.len());
+    let mut numerals = false;
+    for c in s.bytes() {
+        if c == b' ' {
+            v.push(REST);
+        } else if let Some(sig) = to_semaphore(c) {
+            if c.is_ascii_digit() != numerals {
+                numerals = !numerals;
+                v.push(if numerals { NUMERALS } else { LETTERS_SIGN });
+            }
+            v.push(sig);
+        }
+    }
+    v
+}
+
+/// Decodes a sequence of signals, keeping track of switches between
+/// letters mode and numerals mode.  `REST` becomes a space and `CANCEL`
+/// deletes the previous character.  Returns `None` if some signal has
+/// no meaning.
+/// ```
+/// use puzzletools::code::semaphore::{decode, encode};
+/// assert_eq!(decode(encode("ROUTE 66")), Some("ROUTE 66".to_string()));
+/// assert_eq!(decode(vec![(0, 1), (3, 7), (0, 2)]), Some("B".to_string()));
+/// ```
+pub fn decode<I: IntoIterator<Item = Signal>>(it: I) -> Option<String> {
+    let mut s = String::new();
+    let mut numerals = false;
+    for (a, b) in it {
+        match signal(a, b) {
+            REST => s.push(' '),
+            NUMERALS => numerals = true,
+            CANCEL => {
+                s.pop();
+            }
+            LETTERS_SIGN if numerals => numerals = false,
+            _ if numerals => s.push(from_semaphore_numeral(a, b)?),
+            _ => s.push(from_semaphore(a, b)?),
+        }
+    }
+    Some(s)
+}
+
+/// Parses an arm position written as a clock time (`"7:30"`, `"9"`,
+/// `"10:30"`) or a compass direction (`"SW"`, `"n"`), as seen by the
+/// observer.
+/// ```
+/// use puzzletools::code::semaphore::parse_position;
+/// assert_eq!(parse_position("6:00"), Some(0));
+/// assert_eq!(parse_position("7:30"), Some(1));
+/// assert_eq!(parse_position("12"), Some(4));
+/// assert_eq!(parse_position("NE"), Some(5));
+/// assert_eq!(parse_position("8:00"), None);
+/// ```
+pub fn parse_position(s: &str) -> Option<u8> {
+    let s = s.trim();
+    let compass = ["S", "SW", "W", "NW", "N", "NE", "E", "SE"];
+    if let Some(n) = compass.iter().position(|d| d.eq_ignore_ascii_case(s)) {
+        return Some(n as u8);
+    }
+    let (hours, minutes) = match s.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None => (s.parse::<u32>().ok()?, 0),
+    };
+    if !(1..=12).contains(&hours) || (minutes != 0 && minutes != 30) {
+        return None;
+    }
+    let half_hours = (2 * hours + minutes / 30) % 24;
+    if half_hours % 3 == 0 {
+        Some((((half_hours + 12) / 3) % 8) as u8)
+    } else {
+        None
+    }
+}
+
+/// Decodes semaphore written as pairs of positions.  Signals are
+/// separated by whitespace or commas, and the two positions of a signal
+/// are separated by `-` or `/`.  Each position can be written in any of
+/// the forms accepted by `parse_position`.
+/// ```
+/// use puzzletools::code::semaphore::decode_positions;
+/// assert_eq!(decode_positions("6-9 12-7:30 12/3").as_deref(), Some("BKJ"));
+/// assert_eq!(decode_positions("S-E, N-E").as_deref(), Some("FJ"));
+/// ```
+pub fn decode_positions(s: &str) -> Option<String> {
+    let signals: Option<Vec<Signal>> = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+        .map(|t| {
+            let (a, b) = t.split_once(['-', '/'])?;
+            Some(signal(parse_position(a)?, parse_position(b)?))
+        })
+        .collect();
+    decode(signals?)
+}
+
+/// Reflects a signal left to right, as if the signaller were seen in
+/// a mirror.
+/// ```
+/// use puzzletools::code::semaphore::{mirror, to_semaphore};
+/// assert_eq!(mirror(to_semaphore('A').unwrap()), to_semaphore('G').unwrap());
+/// ```
+pub fn mirror(s: Signal) -> Signal {
+    signal(8 - s.0, 8 - s.1)
+}
+
+/// Rotates both arms of a signal clockwise by `eighths` eighths of a
+/// circle.
+/// ```
+/// use puzzletools::code::semaphore::{rotate, to_semaphore};
+/// assert_eq!(rotate(to_semaphore('A').unwrap(), 1), to_semaphore('H').unwrap());
+/// ```
+pub fn rotate(s: Signal, eighths: u8) -> Signal {
+    signal(s.0 + eighths % 8, s.1 + eighths % 8)
+}
+
+/// Returns the letter obtained by mirroring the signal for `c`.
+/// ```
+/// use puzzletools::code::semaphore::mirror_letter;
+/// assert_eq!(mirror_letter('B'), Some('F'));
+/// assert_eq!(mirror_letter('D'), Some('D'));
+/// assert_eq!(mirror_letter('T'), None);
+/// ```
+pub fn mirror_letter<L: Letter>(c: L) -> Option<char> {
+    let (a, b) = mirror(to_semaphore(c)?);
+    from_semaphore(a, b)
+}
+
+/// Returns the letter obtained by rotating the signal for `c`.
+/// ```
+/// use puzzletools::code::semaphore::rotate_letter;
+/// assert_eq!(rotate_letter('H', 2), Some('T'));
+/// assert_eq!(rotate_letter('O', 2), None);
+/// ```
+pub fn rotate_letter<L: Letter>(c: L, eighths: u8) -> Option<char> {
+    let (a, b) = rotate(to_semaphore(c)?, eighths);
+    from_semaphore(a, b)
+}
+
+/// Mirrors every letter of a word, returning `None` if some letter
+/// does not mirror to a letter.
+/// ```
+/// use puzzletools::code::semaphore::mirror_word;
+/// assert_eq!(mirror_word("BAND").as_deref(), Some("FGND"));
+/// ```
+pub fn mirror_word<S: Text>(s: S) -> Option<String> {
+    s.bytes().map(mirror_letter).collect()
+}
+
+/// Rotates every letter of a word, returning `None` if some letter
+/// does not rotate to a letter.
+/// ```
+/// use puzzletools::code::semaphore::rotate_word;
+/// assert_eq!(rotate_word("AHO", 1).as_deref(), Some("HOT"));
+/// ```
+pub fn rotate_word<S: Text>(s: S, eighths: u8) -> Option<String> {
+    s.bytes().map(|c| rotate_letter(c, eighths)).collect()
+}
+
+/// Returns `true` if the signal for the letter looks the same in a mirror.
+/// ```
+/// use puzzletools::code::semaphore::is_symmetric;
+/// let v: String = ('A'..='Z').filter(|&c| is_symmetric(c)).collect();
+/// assert_eq!(v, "DNRU");
+/// ```
+pub fn is_symmetric<L: Letter>(c: L) -> bool {
+    to_semaphore(c).is_some_and(|s| mirror(s) == s)
+}
+
+/// Returns `true` if one of the arms is straight down in the signal for
+/// the letter.
+pub fn has_arm_down<L: Letter>(c: L) -> bool {
+    to_semaphore(c).is_some_and(|(a, _)| a == 0)
+}
@@ -1,12 +1,89 @@
-//! Data sets (NATO alphabet, chemical elements)
+//! Data sets (spelling alphabets, chemical elements)
 
+use crate::letter::{num_to_lett_1, try_lett_to_num_0, Letter};
+use crate::word::Text;
 use std::collections::HashMap;
 
+/// A spelling alphabet, which assigns a code word to each letter.
+#[derive(Clone, Debug)]
+pub struct SpellingAlphabet {
+    pub name: &'static str,
+    /// The letters and their code words, in alphabetical order.  Code
+    /// words are uppercase, with spaces and punctuation removed.
+    pub words: Vec<(char, &'static str)>,
+}
+
+impl SpellingAlphabet {
+    /// Returns the code word for the given letter.
+    /// ```
+    /// use puzzletools::data::spelling_alphabet;
+    /// let lapd = spelling_alphabet("LAPD").unwrap();
+    /// assert_eq!(lapd.word('n'), Some("NORA"));
+    /// ```
+    pub fn word(&self, c: char) -> Option<&'static str> {
+        let c = c.to_uppercase().next()?;
+        self.words.iter().find(|w| w.0 == c).map(|w| w.1)
+    }
+
+    /// Returns the letter with the given code word, ignoring case.
+    /// ```
+    /// use puzzletools::data::spelling_alphabet;
+    /// let greek = spelling_alphabet("Greek").unwrap();
+    /// assert_eq!(greek.letter("SIGMA"), Some('Σ'));
+    /// assert_eq!(greek.letter("SIGNAL"), None);
+    /// assert_eq!(greek.letter("Sigma"), Some('Σ'));
+    /// ```
+    pub fn letter<S: Text>(&self, word: S) -> Option<char> {
+        let word = word.as_str().to_uppercase();
+        self.words.iter().find(|w| w.1 == word).map(|w| w.0)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct ChemicalElement {
     pub number: usize,
     pub symbol: String,
     pub name: String,
+    /// The standard atomic weight, or the mass number of the most stable
+    /// isotope for elements without one.
+    pub mass: f64,
+    /// The group (column) of the element.  Lanthanum through ytterbium
+    /// and actinium through nobelium are not in any group; lutetium and
+    /// lawrencium are in group 3.
+    pub group: Option<usize>,
+    pub period: usize,
+    /// The block (`s`, `p`, `d` or `f`).
+    pub block: char,
+}
+
+impl ChemicalElement {
+    /// Returns the row and column of the element in the usual
+    /// 18-column drawing of the periodic table.  The f-block is drawn
+    /// in rows 9 and 10, starting in column 3, so that it is not
+    /// adjacent to the main table.
+    /// ```
+    /// use puzzletools::data::element_by_number;
+    /// assert_eq!(element_by_number(8).unwrap().position(), (2, 16));
+    /// assert_eq!(element_by_number(58).unwrap().position(), (9, 4));
+    /// ```
+    pub fn position(&self) -> (usize, usize) {
+        match self.group {
+            Some(g) => (self.period, g),
+            None if self.period == 6 => (9, self.number - 54),
+            None => (10, self.number - 86),
+        }
+    }
+
+    /// Returns the letter whose position in the alphabet is the atomic
+    /// number of the element, if there is one.
+    /// ```
+    /// use puzzletools::data::element_by_symbol;
+    /// assert_eq!(element_by_symbol("Fe").unwrap().letter(), Some('Z'));
+    /// assert_eq!(element_by_symbol("Co").unwrap().letter(), None);
+    /// ```
+    pub fn letter(&self) -> Option<char> {
+        num_to_lett_1(self.number)
+    }
 }
 
 /// ```
@@ -33,6 +110,36 @@ lazy_static! {
         include_str!("../data/nato_phonetic_alphabet.txt").lines().collect();
 
 
+    /// Spelling alphabets: NATO, the RAF alphabet used from 1924 to 1942,
+    /// the "Able Baker" alphabet used by the US military from 1941 to 1956,
+    /// the LAPD alphabet, the Western Union alphabet, and the names of the
+    /// Greek letters.
+    /// ```
+    /// use puzzletools::data::SPELLING_ALPHABETS;
+    /// let names: Vec<_> = SPELLING_ALPHABETS.iter().map(|a| a.name).collect();
+    /// assert_eq!(names, vec!["NATO", "RAF 1924", "Able Baker", "LAPD", "Western Union", "Greek"]);
+    /// ```
+    pub static ref SPELLING_ALPHABETS: Vec<SpellingAlphabet> = {
+        let nato = SpellingAlphabet {
+            name: "NATO",
+            words: ('A'..='Z').zip(NATO_ALPHABET.iter().copied()).collect(),
+        };
+        let mut v = vec![nato];
+        for line in include_str!("../data/spelling_alphabets.tsv").lines() {
+            let mut fields = line.split('\t');
+            let (name, letter, word) = (
+                fields.next().unwrap(),
+                fields.next().unwrap().chars().next().unwrap(),
+                fields.next().unwrap(),
+            );
+            if v.last().unwrap().name != name {
+                v.push(SpellingAlphabet { name, words: Vec::new() });
+            }
+            v.last_mut().unwrap().words.push((letter, word));
+        }
+        v
+    };
+
     /// A list chemical elements.
     /// ```
     /// use puzzletools::data::{CHEMICAL_ELEMENTS, map_by};
@@ -52,6 +159,166 @@ lazy_static! {
     static ref CHEMICAL_ELEMENTS_BY_SYMBOL: HashMap<String, &'static ChemicalElement> = map_by(&*CHEMICAL_ELEMENTS, |e| e.symbol.to_ascii_uppercase());
 }
 
+/// Returns the spelling alphabet with the given name.
+pub fn spelling_alphabet(name: &str) -> Option<&'static SpellingAlphabet> {
+    SPELLING_ALPHABETS.iter().find(|a| a.name == name)
+}
+
+/// Returns the letters that the given code word stands for, along with
+/// the alphabets it comes from.
+/// ```
+/// use puzzletools::data::code_word_letters;
+/// let v: Vec<_> = code_word_letters("KING").iter().map(|(a, c)| (a.name, *c)).collect();
+/// assert_eq!(v, vec![("RAF 1924", 'K'), ("Able Baker", 'K'), ("LAPD", 'K'), ("Western Union", 'K')]);
+/// ```
+pub fn code_word_letters<S: Text>(word: S) -> Vec<(&'static SpellingAlphabet, char)> {
+    SPELLING_ALPHABETS
+        .iter()
+        .filter_map(|a| a.letter(word.as_str()).map(|c| (a, c)))
+        .collect()
+}
+
+/// Returns the code words from any spelling alphabet that appear in the
+/// given (uppercase) text, along with their alphabets and letters.
+/// ```
+/// use puzzletools::data::contained_code_words;
+/// let v: Vec<_> = contained_code_words("TOMBOY")
+///     .iter()
+///     .map(|(a, c, w)| (a.name, *c, *w))
+///     .collect();
+/// assert_eq!(v, vec![("LAPD", 'B', "BOY"), ("LAPD", 'T', "TOM")]);
+/// ```
+pub fn contained_code_words<S: Text>(s: S) -> Vec<(&'static SpellingAlphabet, char, &'static str)> {
+    SPELLING_ALPHABETS
+        .iter()
+        .flat_map(|a| a.words.iter().map(move |&(c, w)| (a, c, w)))
+        .filter(|(_, _, w)| s.as_str().contains(w))
+        .collect()
+}
+
+/// Returns the element with the given atomic number.
+/// ```
+/// use puzzletools::data::element_by_number;
+/// assert_eq!(element_by_number(26).unwrap().symbol, "Fe");
+/// assert!(element_by_number(0).is_none());
+/// ```
+pub fn element_by_number(n: usize) -> Option<&'static ChemicalElement> {
+    n.checked_sub(1).and_then(|i| CHEMICAL_ELEMENTS.get(i))
+}
+
+/// Returns the element with the given symbol, ignoring case.
+/// ```
+/// use puzzletools::data::element_by_symbol;
+/// assert_eq!(element_by_symbol("HE").unwrap().name, "Helium");
+/// ```
+pub fn element_by_symbol(s: &str) -> Option<&'static ChemicalElement> {
+    CHEMICAL_ELEMENTS_BY_SYMBOL
+        .get(&s.to_ascii_uppercase())
+        .copied()
+}
+
+/// Returns the element whose atomic number is the position of the given
+/// letter in the alphabet.
+/// ```
+/// use puzzletools::data::element_for_letter;
+/// assert_eq!(element_for_letter('C').unwrap().symbol, "Li");
+/// assert!(element_for_letter('?').is_none());
+/// ```
+pub fn element_for_letter<L: Letter>(c: L) -> Option<&'static ChemicalElement> {
+    element_by_number(try_lett_to_num_0(c)? + 1)
+}
+
+/// Returns the element at the given row and column of the periodic
+/// table (see `ChemicalElement::position`).
+/// ```
+/// use puzzletools::data::element_at;
+/// assert_eq!(element_at(4, 8).unwrap().symbol, "Fe");
+/// assert!(element_at(1, 2).is_none());
+/// ```
+pub fn element_at(row: usize, column: usize) -> Option<&'static ChemicalElement> {
+    CHEMICAL_ELEMENTS
+        .iter()
+        .find(|e| e.position() == (row, column))
+}
+
+/// Returns `true` if the two elements are next to each other in the
+/// periodic table.  If `diagonal` is `true`, diagonally adjacent
+/// elements also count.
+/// ```
+/// use puzzletools::data::{are_adjacent, element_by_symbol};
+/// let c = element_by_symbol("C").unwrap();
+/// let si = element_by_symbol("Si").unwrap();
+/// let p = element_by_symbol("P").unwrap();
+/// assert!(are_adjacent(c, si, false));
+/// assert!(!are_adjacent(c, p, false));
+/// assert!(are_adjacent(c, p, true));
+/// ```
+pub fn are_adjacent(e1: &ChemicalElement, e2: &ChemicalElement, diagonal: bool) -> bool {
+    let (r1, c1) = e1.position();
+    let (r2, c2) = e2.position();
+    let (dr, dc) = (r1.abs_diff(r2), c1.abs_diff(c2));
+    if diagonal {
+        dr <= 1 && dc <= 1 && (dr, dc) != (0, 0)
+    } else {
+        dr + dc == 1
+    }
+}
+
+/// Returns the elements adjacent to the given element.
+/// ```
+/// use puzzletools::data::{element_by_symbol, neighbors};
+/// let n: Vec<_> = neighbors(element_by_symbol("He").unwrap(), false)
+///     .iter()
+///     .map(|e| e.symbol.as_str())
+///     .collect();
+/// assert_eq!(n, vec!["Ne"]);
+/// ```
+pub fn neighbors(e: &ChemicalElement, diagonal: bool) -> Vec<&'static ChemicalElement> {
+    CHEMICAL_ELEMENTS
+        .iter()
+        .filter(|f| are_adjacent(e, f, diagonal))
+        .collect()
+}
+
+/// Returns all ways of writing an uppercase string as a sequence of
+/// element symbols.
+/// ```
+/// use puzzletools::data::element_spellings;
+/// assert_eq!(element_spellings("COIN").len(), 4);
+/// assert!(element_spellings("QUIZ").is_empty());
+/// ```
+pub fn element_spellings(s: &str) -> Vec<Vec<&'static ChemicalElement>> {
+    if s.is_empty() {
+        return vec![Vec::new()];
+    }
+    let mut v = Vec::new();
+    for sz in 1..=s.len().min(2) {
+        if let Some(&e) = s.get(..sz).and_then(|k| CHEMICAL_ELEMENTS_BY_SYMBOL.get(k)) {
+            for mut rest in element_spellings(&s[sz..]) {
+                rest.insert(0, e);
+                v.push(rest);
+            }
+        }
+    }
+    v
+}
+
+/// Returns all ways of writing an uppercase string as a sequence of
+/// element symbols in which consecutive elements are adjacent in the
+/// periodic table.
+/// ```
+/// use puzzletools::data::adjacent_element_spellings;
+/// let v = adjacent_element_spellings("CNO", false);
+/// assert_eq!(v.len(), 1);
+/// assert!(adjacent_element_spellings("COS", false).is_empty());
+/// ```
+pub fn adjacent_element_spellings(s: &str, diagonal: bool) -> Vec<Vec<&'static ChemicalElement>> {
+    element_spellings(s)
+        .into_iter()
+        .filter(|v| v.windows(2).all(|w| are_adjacent(w[0], w[1], diagonal)))
+        .collect()
+}
+
 /// ```
 /// use puzzletools::data::parse_as_element_symbols;
 /// let (freq, symbs) = parse_as_element_symbols("THESOUTH");
@@ -93,3 +360,13 @@ pub fn parse_as_element_symbols(s: &str) -> (u64, Option<Vec<&'static ChemicalEl
     }
     (tot, Some(v))
 }
+
+#[test]
+fn element_positions_test() {
+    let positions: std::collections::HashSet<_> =
+        CHEMICAL_ELEMENTS.iter().map(|e| e.position()).collect();
+    assert_eq!(positions.len(), CHEMICAL_ELEMENTS.len());
+    for (n, e) in CHEMICAL_ELEMENTS.iter().enumerate() {
+        assert_eq!(e.number, n + 1);
+    }
+}
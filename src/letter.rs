@@ -1,7 +1,12 @@
-//! Tests for commonly used properties of individual letters.
+//! Tests for commonly used properties of individual letters, including
+//! how they look (symmetry, holes, strokes, and reflections) and where
+//! they are on a keyboard.
+
+pub mod keyboard;
 
 /// A byte or a character.  Functions in the `puzzletools` crate use
-/// this trait in order to accept either bytes or characters.
+/// this trait in order to accept either bytes or characters.  Non-ASCII
+/// characters are converted to the byte `0xFF`, which is not a letter.
 /// ```
 /// use puzzletools::letter::is_dna_letter;
 /// assert!(is_dna_letter('A'));
@@ -25,26 +30,82 @@ impl Letter for &u8 {
 
 impl Letter for char {
     fn byte(self) -> u8 {
-        self as u8
+        if self.is_ascii() {
+            self as u8
+        } else {
+            0xFF
+        }
     }
 }
 
 impl Letter for &char {
     fn byte(self) -> u8 {
-        *self as u8
+        (*self).byte()
     }
 }
 
 /// Converts an uppercase letter into a number.  This is zero-offset,
 /// so A becomes 0.
+///
+/// # Panics
+/// Panics if `c` is not a letter.  Use `try_lett_to_num_0` for text that
+/// might contain other characters.
 /// ```
 /// use puzzletools::letter::lett_to_num_0;
 /// assert_eq!(lett_to_num_0(b'E'),4);
 /// ```
 pub fn lett_to_num_0(c: impl Letter) -> usize {
-    (c.byte() - b'A') as usize
+    try_lett_to_num_0(c).expect("not a letter")
 }
 
+/// Converts a letter into a number, so that A (or a) becomes 0.
+/// Returns `None` if `c` is not a letter.
+/// ```
+/// use puzzletools::letter::try_lett_to_num_0;
+/// assert_eq!(try_lett_to_num_0('e'), Some(4));
+/// assert_eq!(try_lett_to_num_0('@'), None);
+/// ```
+pub fn try_lett_to_num_0(c: impl Letter) -> Option<usize> {
+    let c = c.byte();
+    if c.is_ascii_alphabetic() {
+        Some((c.to_ascii_uppercase() - b'A') as usize)
+    } else {
+        None
+    }
+}
+
+/// Converts an uppercase letter into a number.  This is one-offset,
+/// so A becomes 1.
+/// ```
+/// use puzzletools::letter::lett_to_num_1;
+/// assert_eq!(lett_to_num_1(b'E'),5);
+/// ```
+pub fn lett_to_num_1(c: impl Letter) -> usize {
+    lett_to_num_0(c) + 1
+}
+
+/// Converts a number into an uppercase letter, so that 1 becomes A and
+/// 26 becomes Z.  Returns `None` if the number is out of range.
+/// ```
+/// use puzzletools::letter::num_to_lett_1;
+/// assert_eq!(num_to_lett_1(5), Some('E'));
+/// assert_eq!(num_to_lett_1(27), None);
+/// ```
+pub fn num_to_lett_1(n: usize) -> Option<char> {
+    if (1..=26).contains(&n) {
+        Some((b'@' + n as u8) as char)
+    } else {
+        None
+    }
+}
+
+/// The relative frequencies of the letters A through Z in English text.
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
 pub fn is_dna_letter<L: Letter>(c: L) -> bool {
     matches!(c.byte(), b'A' | b'C' | b'T' | b'G')
 }
@@ -92,3 +153,216 @@ pub fn scrabble_value<L: Letter>(c: L) -> u32 {
         _ => panic!("invalid letter"),
     }
 }
+
+// The visual properties below refer to capital letters in a plain
+// sans-serif font, except where lowercase letters are mentioned.
+
+/// Returns `true` if the capital letter looks the same when reflected
+/// top to bottom (that is, across a horizontal axis).
+/// ```
+/// use puzzletools::letter::has_horizontal_symmetry;
+/// assert!(has_horizontal_symmetry('B'));
+/// assert!(!has_horizontal_symmetry('A'));
+/// ```
+pub fn has_horizontal_symmetry<L: Letter>(c: L) -> bool {
+    matches!(
+        c.byte(),
+        b'B' | b'C' | b'D' | b'E' | b'H' | b'I' | b'K' | b'O' | b'X'
+    )
+}
+
+/// Returns `true` if the capital letter looks the same when reflected
+/// left to right (that is, across a vertical axis).
+/// ```
+/// use puzzletools::letter::has_vertical_symmetry;
+/// assert!(has_vertical_symmetry('A'));
+/// assert!(!has_vertical_symmetry('B'));
+/// ```
+pub fn has_vertical_symmetry<L: Letter>(c: L) -> bool {
+    matches!(
+        c.byte(),
+        b'A' | b'H' | b'I' | b'M' | b'O' | b'T' | b'U' | b'V' | b'W' | b'X' | b'Y'
+    )
+}
+
+/// Returns `true` if the capital letter looks the same when rotated by
+/// 180 degrees.
+/// ```
+/// use puzzletools::letter::has_rotational_symmetry;
+/// assert!(has_rotational_symmetry('N'));
+/// assert!(!has_rotational_symmetry('M'));
+/// ```
+pub fn has_rotational_symmetry<L: Letter>(c: L) -> bool {
+    matches!(c.byte(), b'H' | b'I' | b'N' | b'O' | b'S' | b'X' | b'Z')
+}
+
+/// Returns the number of enclosed regions (holes) in the capital letter.
+/// ```
+/// use puzzletools::letter::num_holes;
+/// assert_eq!(num_holes('B'), 2);
+/// assert_eq!(num_holes('R'), 1);
+/// assert_eq!(num_holes('S'), 0);
+/// ```
+pub fn num_holes<L: Letter>(c: L) -> u32 {
+    match c.byte() {
+        b'B' => 2,
+        b'A' | b'D' | b'O' | b'P' | b'Q' | b'R' => 1,
+        _ => 0,
+    }
+}
+
+/// Returns `true` if the capital letter consists only of straight lines.
+/// ```
+/// use puzzletools::letter::is_straight_line_letter;
+/// assert!(is_straight_line_letter('K'));
+/// assert!(!is_straight_line_letter('J'));
+/// ```
+pub fn is_straight_line_letter<L: Letter>(c: L) -> bool {
+    matches!(
+        c.byte(),
+        b'A' | b'E'
+            | b'F'
+            | b'H'
+            | b'I'
+            | b'K'
+            | b'L'
+            | b'M'
+            | b'N'
+            | b'T'
+            | b'V'
+            | b'W'
+            | b'X'
+            | b'Y'
+            | b'Z'
+    )
+}
+
+/// Returns the number of strokes in the capital letter, counting each
+/// straight line and each curve as a separate stroke.  Returns 0 for
+/// characters other than capital letters.
+/// ```
+/// use puzzletools::letter::num_strokes;
+/// assert_eq!(num_strokes('E'), 4);
+/// assert_eq!(num_strokes('O'), 1);
+/// assert_eq!(num_strokes('P'), 2);
+/// ```
+pub fn num_strokes<L: Letter>(c: L) -> u32 {
+    match c.byte() {
+        b'C' | b'I' | b'J' | b'O' | b'S' | b'U' => 1,
+        b'D' | b'G' | b'L' | b'P' | b'Q' | b'T' | b'V' | b'X' => 2,
+        b'A' | b'B' | b'F' | b'H' | b'K' | b'N' | b'R' | b'Y' | b'Z' => 3,
+        b'E' | b'M' | b'W' => 4,
+        _ => 0,
+    }
+}
+
+/// Returns the letter that `c` looks like when rotated by 180 degrees,
+/// if there is one.  Works for both capital and lowercase letters.
+/// ```
+/// use puzzletools::letter::upside_down;
+/// assert_eq!(upside_down('M'), Some('W'));
+/// assert_eq!(upside_down('d'), Some('p'));
+/// assert_eq!(upside_down('A'), None);
+/// ```
+pub fn upside_down<L: Letter>(c: L) -> Option<char> {
+    Some(match c.byte() {
+        b'M' => 'W',
+        b'W' => 'M',
+        b'b' => 'q',
+        b'q' => 'b',
+        b'd' => 'p',
+        b'p' => 'd',
+        b'n' => 'u',
+        b'u' => 'n',
+        c @ (b'l' | b'o' | b's' | b'x' | b'z') => c as char,
+        c if has_rotational_symmetry(c) => c as char,
+        _ => return None,
+    })
+}
+
+/// Returns the letter that `c` looks like when reflected left to right,
+/// if there is one.  Works for both capital and lowercase letters.
+/// ```
+/// use puzzletools::letter::mirror;
+/// assert_eq!(mirror('T'), Some('T'));
+/// assert_eq!(mirror('b'), Some('d'));
+/// assert_eq!(mirror('R'), None);
+/// ```
+pub fn mirror<L: Letter>(c: L) -> Option<char> {
+    Some(match c.byte() {
+        b'b' => 'd',
+        b'd' => 'b',
+        b'p' => 'q',
+        b'q' => 'p',
+        c @ (b'i' | b'l' | b'o' | b'v' | b'w' | b'x') => c as char,
+        c if has_vertical_symmetry(c) => c as char,
+        _ => return None,
+    })
+}
+
+/// Returns the letter that `c` looks like when reflected top to bottom,
+/// if there is one.  Works for both capital and lowercase letters.
+/// ```
+/// use puzzletools::letter::flip;
+/// assert_eq!(flip('E'), Some('E'));
+/// assert_eq!(flip('M'), Some('W'));
+/// assert_eq!(flip('b'), Some('p'));
+/// ```
+pub fn flip<L: Letter>(c: L) -> Option<char> {
+    Some(match c.byte() {
+        b'M' => 'W',
+        b'W' => 'M',
+        b'b' => 'p',
+        b'p' => 'b',
+        b'd' => 'q',
+        b'q' => 'd',
+        c @ (b'c' | b'l' | b'o' | b'x') => c as char,
+        c if has_horizontal_symmetry(c) => c as char,
+        _ => return None,
+    })
+}
+
+/// Returns the capital letter that `c` looks like when rotated by 90
+/// degrees clockwise, if there is one.  (I and H are taken to look
+/// alike, as they do in a font with serifs.)
+/// ```
+/// use puzzletools::letter::rotate_clockwise;
+/// assert_eq!(rotate_clockwise('N'), Some('Z'));
+/// assert_eq!(rotate_clockwise('U'), Some('C'));
+/// assert_eq!(rotate_clockwise('C'), None);
+/// ```
+pub fn rotate_clockwise<L: Letter>(c: L) -> Option<char> {
+    Some(match c.byte() {
+        b'U' => 'C',
+        b'W' => 'E',
+        c => return rotate_symmetric(c),
+    })
+}
+
+/// Returns the capital letter that `c` looks like when rotated by 90
+/// degrees counterclockwise, if there is one.
+/// ```
+/// use puzzletools::letter::rotate_counterclockwise;
+/// assert_eq!(rotate_counterclockwise('C'), Some('U'));
+/// assert_eq!(rotate_counterclockwise('M'), Some('E'));
+/// ```
+pub fn rotate_counterclockwise<L: Letter>(c: L) -> Option<char> {
+    Some(match c.byte() {
+        b'C' => 'U',
+        b'M' => 'E',
+        c => return rotate_symmetric(c),
+    })
+}
+
+// Letters that look the same when rotated by 90 degrees in either
+// direction.
+fn rotate_symmetric(c: u8) -> Option<char> {
+    Some(match c {
+        b'H' => 'I',
+        b'I' => 'H',
+        b'N' => 'Z',
+        b'Z' => 'N',
+        b'O' | b'X' => c as char,
+        _ => return None,
+    })
+}
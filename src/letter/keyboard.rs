@@ -0,0 +1,238 @@
+//! Keyboard layouts, for puzzles about typing: which row or hand types a
+//! letter, which keys are adjacent, and ciphers that shift each key.
+
+use crate::letter::Letter;
+use crate::word::Text;
+
+// The three rows of character keys, from top to bottom, not including
+// the number row.
+const QWERTY: [&str; 3] = ["QWERTYUIOP[]", "ASDFGHJKL;'", "ZXCVBNM,./"];
+const DVORAK: [&str; 3] = ["',.PYFGCRL/=", "AOEUIDHTNS-", ";QJKXBMWVZ"];
+const AZERTY: [&str; 3] = ["AZERTYUIOP^$", "QSDFGHJKLM%*", "WXCVBN,;:!"];
+
+// How far each row is shifted to the right, in keys, relative to the
+// top row.
+const ROW_OFFSETS: [f64; 3] = [0.0, 0.25, 0.75];
+
+// Keys in this column and to the right of it are typed with the right
+// hand.
+const RIGHT_HAND_COLUMN: usize = 5;
+
+/// A keyboard layout.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Layout {
+    /// The standard English layout.
+    Qwerty,
+    /// The Dvorak simplified keyboard.
+    Dvorak,
+    /// The standard French layout.
+    Azerty,
+}
+
+/// A hand used for touch typing.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Layout {
+    fn rows(&self) -> &'static [&'static str; 3] {
+        match self {
+            Layout::Qwerty => &QWERTY,
+            Layout::Dvorak => &DVORAK,
+            Layout::Azerty => &AZERTY,
+        }
+    }
+
+    /// Returns the row (0 for the top row of letters, 2 for the bottom)
+    /// and column of the key that types `c`, or `None` if there is no
+    /// such key.  Letters can be either case.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert_eq!(Layout::Qwerty.position('Q'), Some((0, 0)));
+    /// assert_eq!(Layout::Qwerty.position('m'), Some((2, 6)));
+    /// assert_eq!(Layout::Dvorak.position('E'), Some((1, 2)));
+    /// assert_eq!(Layout::Qwerty.position('1'), None);
+    /// ```
+    pub fn position<L: Letter>(&self, c: L) -> Option<(usize, usize)> {
+        let c = c.byte().to_ascii_uppercase();
+        self.rows()
+            .iter()
+            .enumerate()
+            .find_map(|(row, keys)| keys.bytes().position(|k| k == c).map(|col| (row, col)))
+    }
+
+    /// Returns the row of the key that types `c`; see `position`.
+    pub fn row<L: Letter>(&self, c: L) -> Option<usize> {
+        self.position(c).map(|p| p.0)
+    }
+
+    /// Returns the hand that types `c` in standard touch typing.
+    /// ```
+    /// use puzzletools::letter::keyboard::{Hand, Layout};
+    /// assert_eq!(Layout::Qwerty.hand('T'), Some(Hand::Left));
+    /// assert_eq!(Layout::Qwerty.hand('Y'), Some(Hand::Right));
+    /// assert_eq!(Layout::Dvorak.hand('U'), Some(Hand::Left));
+    /// ```
+    pub fn hand<L: Letter>(&self, c: L) -> Option<Hand> {
+        self.position(c).map(|(_, col)| {
+            if col < RIGHT_HAND_COLUMN {
+                Hand::Left
+            } else {
+                Hand::Right
+            }
+        })
+    }
+
+    /// Returns the location of the center of the key that types `c`, as
+    /// `(x, y)`, where keys are one unit wide and `y` is the row.  The
+    /// rows are staggered as on a standard keyboard.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert_eq!(Layout::Qwerty.coordinates('Q'), Some((0.0, 0.0)));
+    /// assert_eq!(Layout::Qwerty.coordinates('Z'), Some((0.75, 2.0)));
+    /// ```
+    pub fn coordinates<L: Letter>(&self, c: L) -> Option<(f64, f64)> {
+        self.position(c)
+            .map(|(row, col)| (col as f64 + ROW_OFFSETS[row], row as f64))
+    }
+
+    /// Returns the distance between the centers of the keys that type
+    /// `c1` and `c2`, in units of one key width.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert_eq!(Layout::Qwerty.distance('Q', 'P'), Some(9.0));
+    /// assert_eq!(Layout::Qwerty.distance('Q', '1'), None);
+    /// ```
+    pub fn distance<L: Letter, M: Letter>(&self, c1: L, c2: M) -> Option<f64> {
+        let (x1, y1) = self.coordinates(c1)?;
+        let (x2, y2) = self.coordinates(c2)?;
+        Some((x1 - x2).hypot(y1 - y2))
+    }
+
+    /// Returns `true` if the keys that type `c1` and `c2` are next to
+    /// each other, either in the same row or diagonally.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert!(Layout::Qwerty.are_adjacent('S', 'D'));
+    /// assert!(Layout::Qwerty.are_adjacent('S', 'W'));
+    /// assert!(Layout::Qwerty.are_adjacent('S', 'Z'));
+    /// assert!(!Layout::Qwerty.are_adjacent('S', 'Q'));
+    /// assert!(!Layout::Qwerty.are_adjacent('S', 'S'));
+    /// ```
+    pub fn are_adjacent<L: Letter, M: Letter>(&self, c1: L, c2: M) -> bool {
+        self.distance(c1, c2).is_some_and(|d| d > 0.0 && d < 1.5)
+    }
+
+    /// Returns `true` if all of the letters of `s` are typed using keys
+    /// in the same row.  Characters other than letters are ignored.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert!(Layout::Qwerty.is_one_row("typewriter"));
+    /// assert!(!Layout::Qwerty.is_one_row("keyboard"));
+    /// assert!(Layout::Dvorak.is_one_row("SHOUTED"));
+    /// ```
+    pub fn is_one_row<S: Text>(&self, s: S) -> bool {
+        let mut rows = s
+            .bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|c| self.row(c));
+        match rows.next() {
+            Some(first) => first.is_some() && rows.all(|r| r == first),
+            None => true,
+        }
+    }
+
+    /// Returns `true` if all of the letters of `s` are typed with the
+    /// given hand.  Characters other than letters are ignored.
+    /// ```
+    /// use puzzletools::letter::keyboard::{Hand, Layout};
+    /// assert!(Layout::Qwerty.is_one_hand("STEWARDESSES", Hand::Left));
+    /// assert!(Layout::Qwerty.is_one_hand("lollipop", Hand::Right));
+    /// assert!(!Layout::Qwerty.is_one_hand("HAND", Hand::Left));
+    /// ```
+    pub fn is_one_hand<S: Text>(&self, s: S, hand: Hand) -> bool {
+        s.bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .all(|c| self.hand(c) == Some(hand))
+    }
+
+    /// Returns `true` if `s` can be typed by sliding a finger across the
+    /// keyboard, that is, if each letter is either the same as the
+    /// previous one or on a key adjacent to it.  Characters other than
+    /// letters are ignored.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert!(Layout::Qwerty.is_adjacent_path("DRESS"));
+    /// assert!(Layout::Qwerty.is_adjacent_path("Tree"));
+    /// assert!(!Layout::Qwerty.is_adjacent_path("TRUE"));
+    /// ```
+    pub fn is_adjacent_path<S: Text>(&self, s: S) -> bool {
+        let letters: Vec<u8> = s
+            .bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        letters
+            .windows(2)
+            .all(|w| w[0] == w[1] || self.are_adjacent(w[0], w[1]))
+    }
+
+    /// Returns the character on the key `n` places to the right of the
+    /// one that types `c` (or to the left, if `n` is negative), in the
+    /// same row.  Returns `None` if `c` is not on the keyboard or the
+    /// shift goes past the end of the row.  Letters are returned in
+    /// uppercase.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert_eq!(Layout::Qwerty.shift('Q', 1), Some('W'));
+    /// assert_eq!(Layout::Qwerty.shift('l', 1), Some(';'));
+    /// assert_eq!(Layout::Qwerty.shift('A', -1), None);
+    /// ```
+    pub fn shift<L: Letter>(&self, c: L, n: isize) -> Option<char> {
+        let (row, col) = self.position(c)?;
+        let col = col.checked_add_signed(n)?;
+        self.rows()[row].as_bytes().get(col).map(|&k| k as char)
+    }
+
+    /// Applies `shift` to every character of `s` that is on the
+    /// keyboard, leaving the others unchanged.  Returns `None` if some
+    /// character would be shifted past the end of its row.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert_eq!(Layout::Qwerty.shift_word("JR;;P", -1), Some("HELLO".to_string()));
+    /// assert_eq!(Layout::Qwerty.shift_word("GO WEST", 1), Some("HP ERDY".to_string()));
+    /// assert_eq!(Layout::Qwerty.shift_word("P]", 1), None);
+    /// ```
+    pub fn shift_word<S: Text>(&self, s: S, n: isize) -> Option<String> {
+        s.as_str()
+            .chars()
+            .map(|c| match self.position(c) {
+                Some(_) => self.shift(c, n),
+                None => Some(c),
+            })
+            .collect()
+    }
+
+    /// Converts text typed on this layout into the text that would have
+    /// been typed by pressing the same keys on `other`.  Characters that
+    /// are not on the keyboard are left unchanged.
+    /// ```
+    /// use puzzletools::letter::keyboard::Layout;
+    /// assert_eq!(Layout::Qwerty.convert("JDPPS", Layout::Dvorak), "HELLO");
+    /// assert_eq!(Layout::Azerty.convert("QZERTY", Layout::Qwerty), "AWERTY");
+    /// ```
+    pub fn convert<S: Text>(&self, s: S, other: Layout) -> String {
+        s.as_str()
+            .chars()
+            .map(|c| match self.position(c) {
+                Some((row, col)) => other.rows()[row]
+                    .as_bytes()
+                    .get(col)
+                    .map_or(c, |&k| k as char),
+                None => c,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,235 @@
+//! Counts of the letters in a word, for anagram arithmetic.
+
+use crate::letter::{try_lett_to_num_0, Letter};
+use crate::word::Text;
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+/// The number of times each letter A through Z appears in some text.
+/// Lowercase letters are counted as uppercase, and other characters are
+/// ignored.
+///
+/// Two words are anagrams if and only if they have the same letter
+/// counts:
+/// ```
+/// use puzzletools::letter_counts::LetterCounts;
+/// assert_eq!(LetterCounts::from_text("Dormitory"), LetterCounts::from_text("dirty room"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct LetterCounts([u32; 26]);
+
+impl LetterCounts {
+    /// Creates an empty set of letter counts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the letters of `s`.
+    /// ```
+    /// use puzzletools::letter_counts::LetterCounts;
+    /// let c = LetterCounts::from_text("Letters!");
+    /// assert_eq!(c.count('T'), 2);
+    /// assert_eq!(c.len(), 7);
+    /// ```
+    pub fn from_text<S: Text>(s: S) -> Self {
+        let mut counts = Self::new();
+        for c in s.bytes() {
+            counts.add_letter(c);
+        }
+        counts
+    }
+
+    /// The number of times the letter `c` appears.  Returns 0 if `c` is
+    /// not a letter.
+    pub fn count<L: Letter>(&self, c: L) -> u32 {
+        try_lett_to_num_0(c).map_or(0, |n| self.0[n])
+    }
+
+    /// Adds one copy of the letter `c`.  Does nothing if `c` is not a
+    /// letter.
+    pub fn add_letter<L: Letter>(&mut self, c: L) {
+        if let Some(n) = try_lett_to_num_0(c) {
+            self.0[n] += 1;
+        }
+    }
+
+    /// Removes one copy of the letter `c`.  Returns `false` (and does
+    /// nothing) if `c` does not appear.
+    /// ```
+    /// use puzzletools::letter_counts::LetterCounts;
+    /// let mut c = LetterCounts::from_text("AB");
+    /// assert!(c.remove_letter('A'));
+    /// assert!(!c.remove_letter('A'));
+    /// assert_eq!(c, LetterCounts::from_text("B"));
+    /// ```
+    pub fn remove_letter<L: Letter>(&mut self, c: L) -> bool {
+        match try_lett_to_num_0(c) {
+            Some(n) if self.0[n] > 0 => {
+                self.0[n] -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The total number of letters.
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|&n| n as usize).sum()
+    }
+
+    /// Returns `true` if there are no letters.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&n| n == 0)
+    }
+
+    /// Returns `true` if every letter appears at least as many times in
+    /// `self` as in `other`, that is, if `other` can be spelled using the
+    /// letters of `self`.
+    /// ```
+    /// use puzzletools::letter_counts::LetterCounts;
+    /// let c = LetterCounts::from_text("PUZZLETOOLS");
+    /// assert!(c.contains(&LetterCounts::from_text("POLLS")));
+    /// assert!(!c.contains(&LetterCounts::from_text("PUPS")));
+    /// ```
+    pub fn contains(&self, other: &Self) -> bool {
+        self.0.iter().zip(&other.0).all(|(a, b)| a >= b)
+    }
+
+    /// Removes the letters of `other`, or returns `None` if `self` does not
+    /// contain them.
+    /// ```
+    /// use puzzletools::letter_counts::LetterCounts;
+    /// let c = LetterCounts::from_text("SPORE");
+    /// assert_eq!(c.checked_sub(&LetterCounts::from_text("ROPE")), Some(LetterCounts::from_text("S")));
+    /// assert_eq!(c.checked_sub(&LetterCounts::from_text("ROPES!?")).map(|c| c.len()), Some(0));
+    /// assert_eq!(c.checked_sub(&LetterCounts::from_text("ROPED")), None);
+    /// ```
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        let mut v = *self;
+        for (a, &b) in v.0.iter_mut().zip(&other.0) {
+            *a = a.checked_sub(b)?;
+        }
+        Some(v)
+    }
+
+    /// Returns the number of letters of `self` that are not in `other`,
+    /// and the number of letters of `other` that are not in `self`.
+    /// ```
+    /// use puzzletools::letter_counts::LetterCounts;
+    /// let c = LetterCounts::from_text("DIFFERENCE");
+    /// assert_eq!(c.difference(&LetterCounts::from_text("AIRDEFENCE")), (1, 1));
+    /// ```
+    pub fn difference(&self, other: &Self) -> (usize, usize) {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .fold((0, 0), |(s, t), (&a, &b)| {
+                (
+                    s + a.saturating_sub(b) as usize,
+                    t + b.saturating_sub(a) as usize,
+                )
+            })
+    }
+
+    /// Iterates over the letters that appear, in alphabetical order, with
+    /// their counts.
+    /// ```
+    /// use puzzletools::letter_counts::LetterCounts;
+    /// let v: Vec<_> = LetterCounts::from_text("BOOK").iter().collect();
+    /// assert_eq!(v, vec![('B', 1), ('K', 1), ('O', 2)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (char, u32)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|&(_, &n)| n > 0)
+            .map(|(i, &n)| ((b'A' + i as u8) as char, n))
+    }
+
+    /// Iterates over the letters in alphabetical order, with each letter
+    /// repeated according to its count.
+    pub fn letters(&self) -> impl Iterator<Item = char> + '_ {
+        self.iter()
+            .flat_map(|(c, n)| std::iter::repeat_n(c, n as usize))
+    }
+
+    /// Returns the letters in alphabetical order.  For uppercase words,
+    /// this is the same as `word::alphagram`.
+    /// ```
+    /// use puzzletools::letter_counts::LetterCounts;
+    /// assert_eq!(LetterCounts::from_text("Spoon").alphagram(), "NOOPS");
+    /// ```
+    pub fn alphagram(&self) -> String {
+        self.letters().collect()
+    }
+}
+
+impl<L: Letter> FromIterator<L> for LetterCounts {
+    fn from_iter<I: IntoIterator<Item = L>>(it: I) -> Self {
+        let mut counts = Self::new();
+        for c in it {
+            counts.add_letter(c);
+        }
+        counts
+    }
+}
+
+impl AddAssign<&LetterCounts> for LetterCounts {
+    fn add_assign(&mut self, other: &LetterCounts) {
+        for (a, &b) in self.0.iter_mut().zip(&other.0) {
+            *a += b;
+        }
+    }
+}
+
+impl AddAssign for LetterCounts {
+    fn add_assign(&mut self, other: LetterCounts) {
+        *self += &other;
+    }
+}
+
+impl Add for LetterCounts {
+    type Output = LetterCounts;
+    fn add(mut self, other: LetterCounts) -> LetterCounts {
+        self += &other;
+        self
+    }
+}
+
+/// Removes letters.  Letters that appear more often in the right hand
+/// side are removed entirely; use `checked_sub` to detect this case.
+impl SubAssign<&LetterCounts> for LetterCounts {
+    fn sub_assign(&mut self, other: &LetterCounts) {
+        for (a, &b) in self.0.iter_mut().zip(&other.0) {
+            *a = a.saturating_sub(b);
+        }
+    }
+}
+
+impl SubAssign for LetterCounts {
+    fn sub_assign(&mut self, other: LetterCounts) {
+        *self -= &other;
+    }
+}
+
+/// ```
+/// use puzzletools::letter_counts::LetterCounts;
+/// let c = LetterCounts::from_text("STAR") - LetterCounts::from_text("RAT");
+/// assert_eq!(c.alphagram(), "S");
+/// ```
+impl Sub for LetterCounts {
+    type Output = LetterCounts;
+    fn sub(mut self, other: LetterCounts) -> LetterCounts {
+        self -= &other;
+        self
+    }
+}
+
+impl Display for LetterCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.letters() {
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
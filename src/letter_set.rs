@@ -0,0 +1,201 @@
+//! Sets of letters, stored as bitmasks.
+
+use crate::letter::{try_lett_to_num_0, Letter};
+use crate::word::Text;
+use std::fmt::{self, Display};
+use std::ops::{BitAnd, BitOr, Not, Sub};
+
+/// A set of letters A through Z.  Lowercase letters are treated as
+/// uppercase, and other characters are ignored.
+/// ```
+/// use puzzletools::letter_set::LetterSet;
+/// let sport = LetterSet::from_text("SPORT");
+/// assert!(LetterSet::from_text("WHEEL").is_disjoint(&sport));
+/// assert!(!LetterSet::from_text("WHEELS").is_disjoint(&sport));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LetterSet(u32);
+
+impl LetterSet {
+    /// The empty set.
+    pub const EMPTY: LetterSet = LetterSet(0);
+    /// The set of all 26 letters.
+    pub const ALL: LetterSet = LetterSet((1 << 26) - 1);
+
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// The set of letters that appear in `s`.
+    /// ```
+    /// use puzzletools::letter_set::LetterSet;
+    /// assert_eq!(LetterSet::from_text("Letters").to_string(), "ELRST");
+    /// ```
+    pub fn from_text<S: Text>(s: S) -> Self {
+        s.bytes().collect()
+    }
+
+    /// The set of letters satisfying a predicate, such as the ones in the
+    /// `letter` module.
+    /// ```
+    /// use puzzletools::letter::is_vowel_no_y;
+    /// use puzzletools::letter_set::LetterSet;
+    /// let vowels = LetterSet::from_predicate(is_vowel_no_y);
+    /// assert_eq!(vowels.to_string(), "AEIOU");
+    /// assert!(LetterSet::from_text("FACETIOUS").is_superset(&vowels));
+    /// ```
+    pub fn from_predicate<F: FnMut(u8) -> bool>(mut pred: F) -> Self {
+        (b'A'..=b'Z').filter(|&c| pred(c)).collect()
+    }
+
+    /// Creates a set from a bitmask, in which bit 0 represents A.  Bits
+    /// beyond the 26th are ignored.
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    /// The bitmask representing the set, in which bit 0 represents A.
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Adds a letter to the set.  Returns `true` if the letter was not
+    /// already in the set, and `false` if it was or if `c` is not a letter.
+    pub fn insert<L: Letter>(&mut self, c: L) -> bool {
+        match try_lett_to_num_0(c) {
+            Some(n) if self.0 & (1 << n) == 0 => {
+                self.0 |= 1 << n;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a letter from the set.  Returns `true` if the letter was in
+    /// the set.
+    pub fn remove<L: Letter>(&mut self, c: L) -> bool {
+        match try_lett_to_num_0(c) {
+            Some(n) if self.0 & (1 << n) != 0 => {
+                self.0 &= !(1 << n);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the letter `c` is in the set.
+    pub fn contains<L: Letter>(&self, c: L) -> bool {
+        try_lett_to_num_0(c).is_some_and(|n| self.0 & (1 << n) != 0)
+    }
+
+    /// The number of letters in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The letters that are in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// The letters that are in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The letters that are in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// The letters that are not in the set.
+    pub fn complement(&self) -> Self {
+        Self(!self.0 & Self::ALL.0)
+    }
+
+    /// Returns `true` if every letter of `self` is in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// Returns `true` if every letter of `other` is in `self`.
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if the sets have no letters in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// Iterates over the letters of the set in alphabetical order.
+    /// ```
+    /// use puzzletools::letter_set::LetterSet;
+    /// let v: Vec<char> = LetterSet::from_text("BANANA").iter().collect();
+    /// assert_eq!(v, vec!['A', 'B', 'N']);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        let bits = self.0;
+        (0..26u8)
+            .filter(move |n| bits & (1 << n) != 0)
+            .map(|n| (b'A' + n) as char)
+    }
+}
+
+impl<L: Letter> FromIterator<L> for LetterSet {
+    fn from_iter<I: IntoIterator<Item = L>>(it: I) -> Self {
+        let mut set = Self::new();
+        for c in it {
+            set.insert(c);
+        }
+        set
+    }
+}
+
+impl BitOr for LetterSet {
+    type Output = LetterSet;
+    fn bitor(self, other: LetterSet) -> LetterSet {
+        self.union(&other)
+    }
+}
+
+impl BitAnd for LetterSet {
+    type Output = LetterSet;
+    fn bitand(self, other: LetterSet) -> LetterSet {
+        self.intersection(&other)
+    }
+}
+
+/// ```
+/// use puzzletools::letter_set::LetterSet;
+/// let s = LetterSet::from_text("STAR") - LetterSet::from_text("RAT");
+/// assert_eq!(s.to_string(), "S");
+/// ```
+impl Sub for LetterSet {
+    type Output = LetterSet;
+    fn sub(self, other: LetterSet) -> LetterSet {
+        self.difference(&other)
+    }
+}
+
+impl Not for LetterSet {
+    type Output = LetterSet;
+    fn not(self) -> LetterSet {
+        self.complement()
+    }
+}
+
+impl Display for LetterSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.iter() {
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
+pub mod cipher;
 pub mod code;
 pub mod data;
 pub mod io;
 pub mod iter;
 pub mod letter;
+pub mod letter_counts;
+pub mod letter_set;
+pub mod number;
+pub mod pronounce;
 pub mod search;
+pub mod value;
 pub mod word;
 pub mod wordlist;
 
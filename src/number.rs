@@ -0,0 +1,378 @@
+//! Roman numerals and numbers written out in English.
+
+use crate::letter::is_roman_numeral_letter;
+use crate::word::Text;
+use std::ops::Range;
+
+const ROMAN: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+/// Writes a number as a Roman numeral.  Returns `None` unless the number
+/// is between 1 and 3999.
+/// ```
+/// use puzzletools::number::to_roman;
+/// assert_eq!(to_roman(1994), Some("MCMXCIV".to_string()));
+/// assert_eq!(to_roman(0), None);
+/// ```
+pub fn to_roman(mut n: u32) -> Option<String> {
+    if !(1..4000).contains(&n) {
+        return None;
+    }
+    let mut s = String::new();
+    for &(v, r) in &ROMAN {
+        while n >= v {
+            s.push_str(r);
+            n -= v;
+        }
+    }
+    Some(s)
+}
+
+fn roman_digit(c: u8) -> Option<u32> {
+    Some(match c.to_ascii_uppercase() {
+        b'I' => 1,
+        b'V' => 5,
+        b'X' => 10,
+        b'L' => 50,
+        b'C' => 100,
+        b'D' => 500,
+        b'M' => 1000,
+        _ => return None,
+    })
+}
+
+/// Parses a Roman numeral, accepting only the standard form of each
+/// number (so `IIII` and `IC` are rejected).  Case is ignored.
+/// ```
+/// use puzzletools::number::parse_roman;
+/// assert_eq!(parse_roman("xiv"), Some(14));
+/// assert_eq!(parse_roman("IIII"), None);
+/// assert_eq!(parse_roman("IC"), None);
+/// ```
+pub fn parse_roman<S: Text>(s: S) -> Option<u32> {
+    let n = parse_roman_lenient(s.as_str())?;
+    if to_roman(n)?.eq_ignore_ascii_case(s.as_str()) {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+/// Parses a Roman numeral leniently: each symbol is added, unless it is
+/// followed by a larger symbol, in which case it is subtracted.  This
+/// accepts nonstandard forms such as `IIII` and `IC`.  Returns `None` if
+/// `s` is empty or contains a character that is not a Roman numeral.
+/// ```
+/// use puzzletools::number::parse_roman_lenient;
+/// assert_eq!(parse_roman_lenient("IIII"), Some(4));
+/// assert_eq!(parse_roman_lenient("IC"), Some(99));
+/// assert_eq!(parse_roman_lenient("MDCCCCX"), Some(1910));
+/// assert_eq!(parse_roman_lenient("XIVA"), None);
+/// ```
+pub fn parse_roman_lenient<S: Text>(s: S) -> Option<u32> {
+    let digits = s.bytes().map(roman_digit).collect::<Option<Vec<u32>>>()?;
+    if digits.is_empty() {
+        return None;
+    }
+    let mut total = 0i64;
+    for (n, &d) in digits.iter().enumerate() {
+        if digits[n + 1..].first().is_some_and(|&next| next > d) {
+            total -= d as i64;
+        } else {
+            total += d as i64;
+        }
+    }
+    u32::try_from(total).ok().filter(|&t| t > 0)
+}
+
+/// A block of consecutive Roman numeral letters in a word.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RomanBlock {
+    /// The position of the block in the word.
+    pub range: Range<usize>,
+    /// The value of the block, if it is a Roman numeral in standard form.
+    pub value: Option<u32>,
+}
+
+// The length of the longest Roman numeral in standard form, MMMDCCCLXXXVIII.
+const MAX_ROMAN_LEN: usize = 15;
+
+/// Finds the maximal blocks of Roman numeral letters (I, V, X, L, C, D
+/// and M) in `s`.  Only uppercase letters are considered.
+/// ```
+/// use puzzletools::number::roman_blocks;
+/// let v = roman_blocks("MIXTURE");
+/// assert_eq!(v.len(), 1);
+/// assert_eq!((v[0].range.clone(), v[0].value), (0..3, Some(1009)));
+/// let v = roman_blocks("EXILIC");
+/// assert_eq!((v[0].range.clone(), v[0].value), (1..6, None));
+/// ```
+pub fn roman_blocks<S: Text>(s: S) -> Vec<RomanBlock> {
+    roman_blocks_by(s, is_roman_numeral_letter)
+}
+
+/// Finds the maximal blocks of letters in `s` satisfying `pred`, which
+/// should only accept Roman numeral letters.  This is useful when a
+/// puzzle only uses some of the numerals; for example, with a predicate
+/// accepting only X, V and I, the M in "MIXTURE" is not part of the block.
+/// ```
+/// use puzzletools::number::roman_blocks_by;
+/// let v = roman_blocks_by("MIXTURE", |c| b"XVI".contains(&c));
+/// assert_eq!((v[0].range.clone(), v[0].value), (1..3, Some(9)));
+/// let v = roman_blocks_by("EXILIC", |c| b"XVI".contains(&c));
+/// assert_eq!(v.iter().map(|b| b.value).collect::<Vec<_>>(), vec![Some(11), Some(1)]);
+/// ```
+pub fn roman_blocks_by<S: Text, F: FnMut(u8) -> bool>(s: S, mut pred: F) -> Vec<RomanBlock> {
+    let bytes = s.as_bytes();
+    let mut v = Vec::new();
+    let mut start = None;
+    for n in 0..=bytes.len() {
+        let roman = bytes.get(n).is_some_and(|&c| pred(c));
+        match (start, roman) {
+            (None, true) => start = Some(n),
+            (Some(st), false) => {
+                v.push(RomanBlock {
+                    range: st..n,
+                    value: parse_roman(&bytes[st..n]),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    v
+}
+
+/// Finds every substring of `s` that is a Roman numeral in standard
+/// form, in order of position and then length.  Only uppercase letters
+/// are considered.
+/// ```
+/// use puzzletools::number::roman_substrings;
+/// let v: Vec<_> = roman_substrings("EXILIC")
+///     .into_iter()
+///     .map(|b| (b.range, b.value.unwrap()))
+///     .collect();
+/// assert_eq!(
+///     v,
+///     vec![(1..2, 10), (1..3, 11), (2..3, 1), (3..4, 50), (3..5, 51), (4..5, 1), (5..6, 100)]
+/// );
+/// ```
+pub fn roman_substrings<S: Text>(s: S) -> Vec<RomanBlock> {
+    let bytes = s.as_bytes();
+    let mut v = Vec::new();
+    for start in 0..bytes.len() {
+        for end in start + 1..=bytes.len().min(start + MAX_ROMAN_LEN) {
+            if !is_roman_numeral_letter(bytes[end - 1]) {
+                break;
+            }
+            if let Some(value) = parse_roman(&bytes[start..end]) {
+                v.push(RomanBlock {
+                    range: start..end,
+                    value: Some(value),
+                });
+            }
+        }
+    }
+    v
+}
+
+const ONES: [&str; 20] = [
+    "ZERO",
+    "ONE",
+    "TWO",
+    "THREE",
+    "FOUR",
+    "FIVE",
+    "SIX",
+    "SEVEN",
+    "EIGHT",
+    "NINE",
+    "TEN",
+    "ELEVEN",
+    "TWELVE",
+    "THIRTEEN",
+    "FOURTEEN",
+    "FIFTEEN",
+    "SIXTEEN",
+    "SEVENTEEN",
+    "EIGHTEEN",
+    "NINETEEN",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "TWENTY", "THIRTY", "FORTY", "FIFTY", "SIXTY", "SEVENTY", "EIGHTY", "NINETY",
+];
+
+const SCALES: [(u64, &str); 6] = [
+    (1_000_000_000_000_000_000, "QUINTILLION"),
+    (1_000_000_000_000_000, "QUADRILLION"),
+    (1_000_000_000_000, "TRILLION"),
+    (1_000_000_000, "BILLION"),
+    (1_000_000, "MILLION"),
+    (1_000, "THOUSAND"),
+];
+
+fn spell_below_thousand(n: u64, words: &mut Vec<String>) {
+    if n >= 100 {
+        words.push(ONES[(n / 100) as usize].to_string());
+        words.push("HUNDRED".to_string());
+    }
+    let n = n % 100;
+    if n >= 20 {
+        let tens = TENS[(n / 10) as usize];
+        if n.is_multiple_of(10) {
+            words.push(tens.to_string());
+        } else {
+            words.push(format!("{}-{}", tens, ONES[(n % 10) as usize]));
+        }
+    } else if n > 0 {
+        words.push(ONES[n as usize].to_string());
+    }
+}
+
+/// Writes a number out in English words, in uppercase and without "and".
+/// ```
+/// use puzzletools::number::spell_number;
+/// assert_eq!(spell_number(0), "ZERO");
+/// assert_eq!(spell_number(42), "FORTY-TWO");
+/// assert_eq!(spell_number(1_000_315), "ONE MILLION THREE HUNDRED FIFTEEN");
+/// ```
+pub fn spell_number(mut n: u64) -> String {
+    if n == 0 {
+        return ONES[0].to_string();
+    }
+    let mut words = Vec::new();
+    for &(scale, name) in &SCALES {
+        if n >= scale {
+            spell_below_thousand(n / scale, &mut words);
+            words.push(name.to_string());
+            n %= scale;
+        }
+    }
+    spell_below_thousand(n, &mut words);
+    words.join(" ")
+}
+
+fn number_word_value(w: &str) -> Option<u64> {
+    if let Some(n) = ONES.iter().position(|&o| o == w) {
+        return Some(n as u64);
+    }
+    TENS.iter()
+        .position(|&t| !t.is_empty() && t == w)
+        .map(|n| 10 * n as u64)
+}
+
+/// Parses a number written out in English words.  Case is ignored, as
+/// are the word "and", hyphens and commas.  Forms such as "twelve
+/// hundred" are accepted.  Returns `None` if some word is not part of a
+/// number, or if the number is too large.
+/// ```
+/// use puzzletools::number::parse_number;
+/// assert_eq!(parse_number("Forty-two"), Some(42));
+/// assert_eq!(parse_number("one thousand, three hundred and fifteen"), Some(1315));
+/// assert_eq!(parse_number("twelve hundred"), Some(1200));
+/// assert_eq!(parse_number("forty bananas"), None);
+/// ```
+pub fn parse_number<S: Text>(s: S) -> Option<u64> {
+    let upper = s.as_str().to_ascii_uppercase();
+    let mut total: u64 = 0;
+    let mut current: u64 = 0;
+    let mut any = false;
+    for w in upper
+        .split(|c: char| c.is_whitespace() || c == '-' || c == ',')
+        .filter(|w| !w.is_empty() && *w != "AND")
+    {
+        any = true;
+        if let Some(n) = number_word_value(w) {
+            current = current.checked_add(n)?;
+        } else if w == "HUNDRED" {
+            current = current.max(1).checked_mul(100)?;
+        } else if let Some(&(scale, _)) = SCALES.iter().find(|&&(_, name)| name == w) {
+            total = total.checked_add(current.max(1).checked_mul(scale)?)?;
+            current = 0;
+        } else {
+            return None;
+        }
+    }
+    if any {
+        total.checked_add(current)
+    } else {
+        None
+    }
+}
+
+/// A number word found inside another word.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HiddenNumber {
+    /// The position of the number word.
+    pub range: Range<usize>,
+    /// The number word, such as `"SEVEN"` or `"HUNDRED"`.
+    pub word: &'static str,
+    /// The value of the number word.
+    pub value: u64,
+}
+
+/// Finds the number words (zero through nineteen, the multiples of ten,
+/// and the words for powers of ten) that appear in `s`, ordered by
+/// position.  Overlapping number words are all reported.  Only uppercase
+/// letters are matched, so `s` is usually a slug.
+/// ```
+/// use puzzletools::number::hidden_numbers;
+/// let v: Vec<_> = hidden_numbers("CANONE").iter().map(|h| h.word).collect();
+/// assert_eq!(v, vec!["ONE"]);
+/// let v: Vec<_> = hidden_numbers("WEIGHTEN").iter().map(|h| (h.word, h.value)).collect();
+/// assert_eq!(v, vec![("EIGHT", 8), ("TEN", 10)]);
+/// ```
+///
+/// To search a wordlist:
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::number::hidden_numbers;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("OFTEN,10\nOFT,5\nSTONE,3")).unwrap();
+/// let v: Vec<_> = wl
+///     .iter()
+///     .filter(|e| !hidden_numbers(&e.slug).is_empty())
+///     .map(|e| e.word.as_str())
+///     .collect();
+/// assert_eq!(v, vec!["OFTEN", "STONE"]);
+/// ```
+pub fn hidden_numbers<S: Text>(s: S) -> Vec<HiddenNumber> {
+    let words = ONES
+        .iter()
+        .enumerate()
+        .map(|(n, &w)| (w, n as u64))
+        .chain(
+            TENS.iter()
+                .enumerate()
+                .filter(|(_, w)| !w.is_empty())
+                .map(|(n, &w)| (w, 10 * n as u64)),
+        )
+        .chain(std::iter::once(("HUNDRED", 100)))
+        .chain(SCALES.iter().map(|&(v, w)| (w, v)));
+    let s = s.as_str();
+    let mut v: Vec<HiddenNumber> = words
+        .flat_map(|(word, value)| {
+            s.match_indices(word).map(move |(start, _)| HiddenNumber {
+                range: start..start + word.len(),
+                word,
+                value,
+            })
+        })
+        .collect();
+    v.sort_by_key(|h| (h.range.start, h.range.end));
+    v
+}
@@ -0,0 +1,466 @@
+//! Pronunciations from a dictionary in the format of the CMU Pronouncing
+//! Dictionary, for homophones, rhymes, syllables and stress.
+//!
+//! Each line of the dictionary is a word followed by its phonemes, such
+//! as `CAT  K AE1 T`.  Vowels end with a stress marker: 1 for primary
+//! stress, 2 for secondary stress, and 0 for no stress.  Alternate
+//! pronunciations are written `WORD(1)`, and lines starting with `;;;`
+//! (or the parts of lines after `#`) are comments.
+//!
+//! Sounds are compared without their stress markers.  Queries that
+//! return words look them up in a `Wordlist`, so only words in the
+//! wordlist are returned, from most to least frequent.
+
+use crate::error::Result;
+use crate::search::SearchResult;
+use crate::word::{slugify, Text};
+use crate::wordlist::{load_wordlist_file, Wordlist, WordlistEntry};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::io::{BufRead, BufReader, Read};
+
+/// The pronunciation of a word, as a sequence of phonemes.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Pronunciation(Vec<String>);
+
+impl Pronunciation {
+    /// Parses a pronunciation from phonemes separated by spaces.
+    /// ```
+    /// use puzzletools::pronounce::Pronunciation;
+    /// let p = Pronunciation::parse("b ah1 t ER0");
+    /// assert_eq!(p.to_string(), "B AH1 T ER0");
+    /// ```
+    pub fn parse(s: &str) -> Self {
+        Pronunciation(s.split_whitespace().map(str::to_ascii_uppercase).collect())
+    }
+
+    /// The phonemes, with their stress markers.
+    pub fn phonemes(&self) -> &[String] {
+        &self.0
+    }
+
+    /// The number of syllables, which is the number of vowels.
+    /// ```
+    /// use puzzletools::pronounce::Pronunciation;
+    /// assert_eq!(Pronunciation::parse("B AH1 T ER0").syllables(), 2);
+    /// ```
+    pub fn syllables(&self) -> usize {
+        self.0.iter().filter(|p| is_vowel(p)).count()
+    }
+
+    /// The stress markers of the vowels, in order.
+    /// ```
+    /// use puzzletools::pronounce::Pronunciation;
+    /// assert_eq!(Pronunciation::parse("B AH1 T ER0").stress(), "10");
+    /// ```
+    pub fn stress(&self) -> String {
+        self.0
+            .iter()
+            .filter_map(|p| p.chars().last().filter(char::is_ascii_digit))
+            .collect()
+    }
+
+    /// The part of the pronunciation that has to match for two words to
+    /// rhyme: the last stressed vowel and everything after it.  If no
+    /// vowel is stressed, this starts at the last vowel.
+    /// ```
+    /// use puzzletools::pronounce::Pronunciation;
+    /// let p = Pronunciation::parse("IH0 K S P L EY1 N IH0 NG");
+    /// assert_eq!(p.rhyme().join(" "), "EY1 N IH0 NG");
+    /// ```
+    pub fn rhyme(&self) -> &[String] {
+        let start = self
+            .0
+            .iter()
+            .rposition(|p| p.ends_with('1') || p.ends_with('2'))
+            .or_else(|| self.0.iter().rposition(|p| is_vowel(p)))
+            .unwrap_or(0);
+        &self.0[start..]
+    }
+
+    // The sound of the pronunciation, with stress markers removed.
+    fn key(&self) -> String {
+        sound_key(&self.0)
+    }
+}
+
+impl Display for Pronunciation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join(" "))
+    }
+}
+
+fn is_vowel(phoneme: &str) -> bool {
+    phoneme.ends_with(|c: char| c.is_ascii_digit())
+}
+
+fn strip_stress(phoneme: &str) -> &str {
+    phoneme.trim_end_matches(|c: char| c.is_ascii_digit())
+}
+
+fn sound_key<S: AsRef<str>>(phonemes: &[S]) -> String {
+    let v: Vec<&str> = phonemes.iter().map(|p| strip_stress(p.as_ref())).collect();
+    v.join(" ")
+}
+
+// Returns `true` if the rhyming parts of two words have the same vowels
+// but different consonants, or the same (nonempty) consonants but
+// different vowels.
+fn is_slant_rhyme(r1: &str, r2: &str) -> bool {
+    if r1 == r2 {
+        return false;
+    }
+    let (v1, c1) = split(r1);
+    let (v2, c2) = split(r2);
+    v1 == v2 || (c1 == c2 && !c1.is_empty())
+}
+
+// Splits a sound into its vowels and its consonants.
+fn split(sound: &str) -> (Vec<&str>, Vec<&str>) {
+    sound.split(' ').partition(|p| is_vowel_sound(p))
+}
+
+// Vowel phonemes in the CMU dictionary all begin with a vowel letter.
+fn is_vowel_sound(phoneme: &str) -> bool {
+    matches!(
+        phoneme.bytes().next(),
+        Some(b'A' | b'E' | b'I' | b'O' | b'U')
+    )
+}
+
+/// A word obtained by adding or removing a sound.
+#[derive(Clone, Debug)]
+pub struct SoundEdit<'a> {
+    pub entry: &'a WordlistEntry,
+    /// The sound that was added or removed, without a stress marker.
+    pub phoneme: String,
+}
+
+impl SearchResult for SoundEdit<'_> {
+    type Data = String;
+    type Freq = u64;
+    fn data(&self) -> String {
+        format!("{} ({})", self.entry.word, self.phoneme)
+    }
+    fn freq(&self) -> u64 {
+        self.entry.freq
+    }
+}
+
+/// A pronouncing dictionary, mapping slugs to their pronunciations.
+pub struct PronouncingDictionary {
+    entries: HashMap<String, Vec<Pronunciation>>,
+    // Slugs, indexed by their sounds.
+    sounds: HashMap<String, Vec<String>>,
+    // Slugs, indexed by the sounds of their rhyming parts.
+    rhymes: HashMap<String, Vec<String>>,
+}
+
+impl PronouncingDictionary {
+    /// Reads a dictionary.  Lines that cannot be read as UTF-8 are read
+    /// with replacement characters, and lines without phonemes are
+    /// skipped.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::pronounce::PronouncingDictionary;
+    /// let text = ";;; comment\nREAD  R EH1 D\nREAD(1)  R IY1 D\nred r eh1 d # lowercase\n";
+    /// let dict = PronouncingDictionary::load_from_reader(Cursor::new(text)).unwrap();
+    /// assert_eq!(dict.len(), 2);
+    /// assert_eq!(dict.get("read").len(), 2);
+    /// assert_eq!(dict.get("RED")[0].to_string(), "R EH1 D");
+    /// ```
+    pub fn load_from_reader<R: Read>(r: R) -> Result<Self> {
+        let mut dict = PronouncingDictionary {
+            entries: HashMap::new(),
+            sounds: HashMap::new(),
+            rhymes: HashMap::new(),
+        };
+        let mut r = BufReader::new(r);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            if r.read_until(b'\n', &mut buf)? == 0 {
+                break;
+            }
+            let line = String::from_utf8_lossy(&buf);
+            if line.starts_with(";;;") {
+                continue;
+            }
+            let line = line.split('#').next().unwrap_or("");
+            let mut tokens = line.split_whitespace();
+            let Some(word) = tokens.next() else {
+                continue;
+            };
+            let word = match word.find('(') {
+                Some(n) if word.ends_with(')') && n > 0 => &word[..n],
+                _ => word,
+            };
+            let slug = slugify(word).into_owned();
+            let p = Pronunciation(tokens.map(str::to_ascii_uppercase).collect());
+            if !slug.is_empty() && !p.0.is_empty() {
+                dict.insert(slug, p);
+            }
+        }
+        Ok(dict)
+    }
+
+    /// Reads a dictionary from a file in the wordlist directory.
+    pub fn load(name: &str) -> Result<Self> {
+        let r = load_wordlist_file(name)?;
+        Self::load_from_reader(r)
+    }
+
+    fn insert(&mut self, slug: String, p: Pronunciation) {
+        let prons = self.entries.entry(slug.clone()).or_default();
+        if prons.contains(&p) {
+            return;
+        }
+        for (map, key) in [
+            (&mut self.sounds, p.key()),
+            (&mut self.rhymes, sound_key(p.rhyme())),
+        ] {
+            let v = map.entry(key).or_default();
+            if !v.contains(&slug) {
+                v.push(slug.clone());
+            }
+        }
+        prons.push(p);
+    }
+
+    /// The number of words in the dictionary.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the dictionary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the pronunciations of a word.  The word is converted to a
+    /// slug first, so case and punctuation are ignored.
+    pub fn get<S: Text>(&self, word: S) -> &[Pronunciation] {
+        self.entries
+            .get(slugify(word.as_str()).as_ref())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the number of syllables in the first pronunciation of a
+    /// word, or `None` if the word is not in the dictionary.
+    pub fn syllables<S: Text>(&self, word: S) -> Option<usize> {
+        self.get(word).first().map(Pronunciation::syllables)
+    }
+
+    /// Returns the stress pattern of the first pronunciation of a word
+    /// (see `Pronunciation::stress`).
+    pub fn stress<S: Text>(&self, word: S) -> Option<String> {
+        self.get(word).first().map(Pronunciation::stress)
+    }
+
+    // Looks up slugs in the wordlist, leaving out `word` itself.
+    fn lookup<'a, 'b, I>(&self, slugs: I, word: &str, wl: &'a Wordlist) -> Vec<&'a WordlistEntry>
+    where
+        I: IntoIterator<Item = &'b String>,
+    {
+        let mut v: Vec<&'a WordlistEntry> = Vec::new();
+        for slug in slugs {
+            if slug == word {
+                continue;
+            }
+            if let Some(e) = wl.get(slug.as_str())
+                && !v.iter().any(|&f| std::ptr::eq(e, f))
+            {
+                v.push(e);
+            }
+        }
+        v.sort_by(|a, b| b.freq.cmp(&a.freq).then_with(|| a.slug.cmp(&b.slug)));
+        v
+    }
+
+    /// Returns the words that sound the same as some pronunciation of
+    /// `word`.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::pronounce::PronouncingDictionary;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let dict = PronouncingDictionary::load_from_reader(Cursor::new(
+    ///     "READ  R EH1 D\nREAD(1)  R IY1 D\nRED  R EH1 D\nREED  R IY1 D\nRID  R IH1 D",
+    /// )).unwrap();
+    /// let wl = Wordlist::load_from_reader(Cursor::new("READ,10\nRED,8\nREED,3\nRID,2")).unwrap();
+    /// let v: Vec<_> = dict.homophones("read", &wl).iter().map(|e| e.word.as_str()).collect();
+    /// assert_eq!(v, vec!["RED", "REED"]);
+    /// ```
+    pub fn homophones<'a, S: Text>(&self, word: S, wl: &'a Wordlist) -> Vec<&'a WordlistEntry> {
+        let slug = slugify(word.as_str());
+        let slugs = self
+            .get(slug.as_ref())
+            .iter()
+            .filter_map(|p| self.sounds.get(&p.key()))
+            .flatten();
+        self.lookup(slugs, &slug, wl)
+    }
+
+    /// Returns the words that rhyme perfectly with some pronunciation of
+    /// `word`, that is, the words whose pronunciations match from the
+    /// last stressed vowel on.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::pronounce::PronouncingDictionary;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let dict = PronouncingDictionary::load_from_reader(Cursor::new(
+    ///     "CAT  K AE1 T\nHAT  HH AE1 T\nCAP  K AE1 P\nACROBAT  AE1 K R AH0 B AE2 T",
+    /// )).unwrap();
+    /// let wl = Wordlist::load_from_reader(Cursor::new("CAT,10\nHAT,8\nCAP,5\nACROBAT,1")).unwrap();
+    /// let v: Vec<_> = dict.perfect_rhymes("cat", &wl).iter().map(|e| e.word.as_str()).collect();
+    /// assert_eq!(v, vec!["HAT", "ACROBAT"]);
+    /// ```
+    pub fn perfect_rhymes<'a, S: Text>(&self, word: S, wl: &'a Wordlist) -> Vec<&'a WordlistEntry> {
+        let slug = slugify(word.as_str());
+        let slugs = self
+            .get(slug.as_ref())
+            .iter()
+            .filter_map(|p| self.rhymes.get(&sound_key(p.rhyme())))
+            .flatten();
+        self.lookup(slugs, &slug, wl)
+    }
+
+    /// Returns the words that are slant rhymes of some pronunciation of
+    /// `word`: the parts that would have to match for a perfect rhyme
+    /// have the same vowels but different consonants, or the same
+    /// consonants but different vowels.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::pronounce::PronouncingDictionary;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let dict = PronouncingDictionary::load_from_reader(Cursor::new(
+    ///     "TIME  T AY1 M\nLINE  L AY1 N\nTAME  T EY1 M\nRHYME  R AY1 M\nSEE  S IY1\nSO  S OW1",
+    /// )).unwrap();
+    /// let wl = Wordlist::load_from_reader(Cursor::new("TIME,10\nLINE,8\nTAME,5\nRHYME,4\nSEE,3\nSO,2")).unwrap();
+    /// let v: Vec<_> = dict.slant_rhymes("time", &wl).iter().map(|e| e.word.as_str()).collect();
+    /// assert_eq!(v, vec!["LINE", "TAME"]);
+    /// assert!(dict.slant_rhymes("see", &wl).is_empty());
+    /// ```
+    pub fn slant_rhymes<'a, S: Text>(&self, word: S, wl: &'a Wordlist) -> Vec<&'a WordlistEntry> {
+        let slug = slugify(word.as_str());
+        let keys: Vec<String> = self
+            .get(slug.as_ref())
+            .iter()
+            .map(|p| sound_key(p.rhyme()))
+            .collect();
+        let slugs = self
+            .rhymes
+            .iter()
+            .filter(|(r, _)| keys.iter().any(|k| is_slant_rhyme(k, r)))
+            .flat_map(|(_, v)| v);
+        self.lookup(slugs, &slug, wl)
+    }
+
+    /// Returns the words with some pronunciation that has the given
+    /// stress pattern (see `Pronunciation::stress`).
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::pronounce::PronouncingDictionary;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let dict = PronouncingDictionary::load_from_reader(Cursor::new(
+    ///     "BANANA  B AH0 N AE1 N AH0\nAPPLE  AE1 P AH0 L\nTOMATO  T AH0 M EY1 T OW2",
+    /// )).unwrap();
+    /// let wl = Wordlist::load_from_reader(Cursor::new("BANANA,3\nAPPLE,5\nTOMATO,2")).unwrap();
+    /// let v: Vec<_> = dict.with_stress("010", &wl).iter().map(|e| e.word.as_str()).collect();
+    /// assert_eq!(v, vec!["BANANA"]);
+    /// ```
+    pub fn with_stress<'a>(&self, pattern: &str, wl: &'a Wordlist) -> Vec<&'a WordlistEntry> {
+        let slugs = self
+            .entries
+            .iter()
+            .filter(|(_, prons)| prons.iter().any(|p| p.stress() == pattern))
+            .map(|(slug, _)| slug);
+        self.lookup(slugs, "", wl)
+    }
+
+    /// Returns the words that can be made by removing one sound from
+    /// some pronunciation of `word`.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::pronounce::PronouncingDictionary;
+    /// use puzzletools::search::SearchResult;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let dict = PronouncingDictionary::load_from_reader(Cursor::new(
+    ///     "SCAT  S K AE1 T\nCAT  K AE1 T\nSAT  S AE1 T\nSCAB  S K AE1 B",
+    /// )).unwrap();
+    /// let wl = Wordlist::load_from_reader(Cursor::new("SCAT,1\nCAT,10\nSAT,5\nSCAB,2")).unwrap();
+    /// let v: Vec<_> = dict.remove_sound("scat", &wl).iter().map(|e| e.data()).collect();
+    /// assert_eq!(v, vec!["CAT (S)", "SAT (K)"]);
+    /// ```
+    pub fn remove_sound<'a, S: Text>(&self, word: S, wl: &'a Wordlist) -> Vec<SoundEdit<'a>> {
+        let slug = slugify(word.as_str());
+        let mut edits = Vec::new();
+        for p in self.get(slug.as_ref()) {
+            for i in 0..p.0.len() {
+                let mut v = p.0.clone();
+                let removed = v.remove(i);
+                if let Some(slugs) = self.sounds.get(&sound_key(&v)) {
+                    for entry in self.lookup(slugs, &slug, wl) {
+                        edits.push(SoundEdit {
+                            entry,
+                            phoneme: strip_stress(&removed).to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        sort_edits(edits)
+    }
+
+    /// Returns the words that can be made by adding one sound to some
+    /// pronunciation of `word`.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::pronounce::PronouncingDictionary;
+    /// use puzzletools::search::SearchResult;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let dict = PronouncingDictionary::load_from_reader(Cursor::new(
+    ///     "SCAT  S K AE1 T\nCAT  K AE1 T\nCAST  K AE1 S T\nCUT  K AH1 T",
+    /// )).unwrap();
+    /// let wl = Wordlist::load_from_reader(Cursor::new("SCAT,1\nCAT,10\nCAST,5\nCUT,8")).unwrap();
+    /// let v: Vec<_> = dict.add_sound("cat", &wl).iter().map(|e| e.data()).collect();
+    /// assert_eq!(v, vec!["CAST (S)", "SCAT (S)"]);
+    /// ```
+    pub fn add_sound<'a, S: Text>(&self, word: S, wl: &'a Wordlist) -> Vec<SoundEdit<'a>> {
+        let slug = slugify(word.as_str());
+        let targets: Vec<Vec<&str>> = self
+            .get(slug.as_ref())
+            .iter()
+            .map(|p| p.0.iter().map(|s| strip_stress(s)).collect())
+            .collect();
+        let mut edits = Vec::new();
+        for (key, slugs) in &self.sounds {
+            let sounds: Vec<&str> = key.split(' ').collect();
+            for target in targets.iter().filter(|t| t.len() + 1 == sounds.len()) {
+                for i in 0..sounds.len() {
+                    if sounds[..i] == target[..i] && sounds[i + 1..] == target[i..] {
+                        for entry in self.lookup(slugs, &slug, wl) {
+                            edits.push(SoundEdit {
+                                entry,
+                                phoneme: sounds[i].to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        sort_edits(edits)
+    }
+}
+
+// Sorts edits from most to least frequent, and removes duplicates.
+fn sort_edits(mut edits: Vec<SoundEdit<'_>>) -> Vec<SoundEdit<'_>> {
+    edits.sort_by(|a, b| {
+        b.entry
+            .freq
+            .cmp(&a.entry.freq)
+            .then_with(|| a.entry.slug.cmp(&b.entry.slug))
+            .then_with(|| a.phoneme.cmp(&b.phoneme))
+    });
+    edits.dedup_by(|a, b| std::ptr::eq(a.entry, b.entry) && a.phoneme == b.phoneme);
+    edits
+}
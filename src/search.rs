@@ -14,6 +14,32 @@ pub trait SearchResult {
     fn freq(&self) -> Self::Freq;
 }
 
+/// A floating point score (for example, a log likelihood), which can be
+/// used as the `Freq` of a search result.  Scores are compared using
+/// `f64::total_cmp`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Score(pub f64);
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.2}", self.0)
+    }
+}
+
 /// Prints out a search result.
 pub fn print_result<S: SearchResult>(s: S) {
     println!("{}, {}", s.data(), s.freq());
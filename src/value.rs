@@ -0,0 +1,228 @@
+//! Numeric values of letters and words, and arithmetic with letters.
+
+use crate::letter::{lett_to_num_0, scrabble_value};
+use crate::search::{sort_results, SearchResult};
+use crate::word::Text;
+use crate::wordlist::{Wordlist, WordlistEntry, WordlistIndex};
+use std::collections::HashMap;
+
+/// A way of assigning a number to each letter.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LetterValue {
+    /// A is 1, B is 2, and so on up to Z, which is 26.
+    A1Z26,
+    /// Z is 1, Y is 2, and so on up to A, which is 26.
+    ReverseA1Z26,
+    /// The value of the letter in Scrabble.
+    Scrabble,
+    /// The values of the letters A through Z.
+    Custom([u32; 26]),
+}
+
+impl LetterValue {
+    /// The value of a letter, or `None` if `c` is not a letter.  Lowercase
+    /// letters have the same value as uppercase letters.
+    /// ```
+    /// use puzzletools::value::LetterValue;
+    /// assert_eq!(LetterValue::A1Z26.letter('e'), Some(5));
+    /// assert_eq!(LetterValue::ReverseA1Z26.letter('E'), Some(22));
+    /// assert_eq!(LetterValue::Scrabble.letter('Q'), Some(10));
+    /// assert_eq!(LetterValue::A1Z26.letter('-'), None);
+    /// ```
+    pub fn letter(&self, c: char) -> Option<u32> {
+        if !c.is_ascii_alphabetic() {
+            return None;
+        }
+        let c = c.to_ascii_uppercase();
+        let n = lett_to_num_0(c);
+        Some(match self {
+            LetterValue::A1Z26 => n as u32 + 1,
+            LetterValue::ReverseA1Z26 => 26 - n as u32,
+            LetterValue::Scrabble => scrabble_value(c),
+            LetterValue::Custom(table) => table[n],
+        })
+    }
+
+    /// The sum of the values of the letters of `s`.  Other characters are
+    /// ignored.
+    /// ```
+    /// use puzzletools::value::LetterValue;
+    /// assert_eq!(LetterValue::A1Z26.sum("Puzzle"), 16 + 21 + 26 + 26 + 12 + 5);
+    /// assert_eq!(LetterValue::Scrabble.sum("QUIZ"), 22);
+    /// ```
+    pub fn sum<S: Text>(&self, s: S) -> u32 {
+        s.chars().filter_map(|c| self.letter(c)).sum()
+    }
+
+    /// The product of the values of the letters of `s`.  Other characters
+    /// are ignored.  Returns `None` if the product does not fit in a
+    /// `u64`, which can happen for words of about 14 or more letters.
+    /// ```
+    /// use puzzletools::value::LetterValue;
+    /// assert_eq!(LetterValue::A1Z26.product("CAB"), Some(6));
+    /// assert_eq!(LetterValue::A1Z26.product("ZZZZZZZZZZZZZZ"), None);
+    /// ```
+    pub fn product<S: Text>(&self, s: S) -> Option<u64> {
+        s.chars()
+            .filter_map(|c| self.letter(c))
+            .try_fold(1u64, |p, v| p.checked_mul(u64::from(v)))
+    }
+}
+
+/// Makes a custom table of letter values.  Letters that do not appear
+/// in `values` have value 0.
+/// ```
+/// use puzzletools::value::custom_values;
+/// let v = custom_values(&[('A', 1), ('B', 2), ('Z', 100)]);
+/// assert_eq!(v.sum("ABZ"), 103);
+/// assert_eq!(v.sum("CAT"), 1);
+/// ```
+pub fn custom_values(values: &[(char, u32)]) -> LetterValue {
+    let mut table = [0; 26];
+    for &(c, v) in values {
+        if c.is_ascii_alphabetic() {
+            table[lett_to_num_0(c.to_ascii_uppercase())] = v;
+        }
+    }
+    LetterValue::Custom(table)
+}
+
+/// An index of the entries of a wordlist by the sum of their letter
+/// values.
+pub struct ValueIndex<'a> {
+    index: WordlistIndex<'a, u32>,
+}
+
+impl<'a> ValueIndex<'a> {
+    pub fn new(wl: &'a Wordlist, values: LetterValue) -> Self {
+        Self {
+            index: wl.index_by(|e| Some(values.sum(&e.slug))),
+        }
+    }
+
+    /// Returns the entries whose letters sum to `sum`, from most to least
+    /// common.  If `len` is given, only entries with that many letters
+    /// are returned.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::value::{LetterValue, ValueIndex};
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("CAT,5\nDOG,8\nACT,3\nBAT,2\nAW,1")).unwrap();
+    /// let index = ValueIndex::new(&wl, LetterValue::A1Z26);
+    /// let words: Vec<_> = index.get(24, None).map(|e| e.word.as_str()).collect();
+    /// assert_eq!(words, vec!["CAT", "ACT", "AW"]);
+    /// let words: Vec<_> = index.get(24, Some(2)).map(|e| e.word.as_str()).collect();
+    /// assert_eq!(words, vec!["AW"]);
+    /// ```
+    pub fn get(&self, sum: u32, len: Option<usize>) -> impl Iterator<Item = &'a WordlistEntry> {
+        self.index
+            .get(&sum)
+            .iter()
+            .copied()
+            .filter(move |e| len.is_none_or(|l| e.len() == l))
+    }
+}
+
+fn word_numbers<S: Text>(s: S) -> Vec<u8> {
+    s.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|c| lett_to_num_0(c.to_ascii_uppercase()) as u8 + 1)
+        .collect()
+}
+
+fn combine_words<S: Text, T: Text>(s: S, t: T, f: impl Fn(u8, u8) -> u8) -> Option<String> {
+    let s = word_numbers(s);
+    let t = word_numbers(t);
+    if s.len() != t.len() {
+        return None;
+    }
+    Some(
+        s.iter()
+            .zip(&t)
+            .map(|(&a, &b)| (b'A' + (f(a, b) + 25) % 26) as char)
+            .collect(),
+    )
+}
+
+/// Adds two words letter by letter, where A is 1, B is 2, and so on,
+/// modulo 26 (so Z acts as 0).  Returns `None` if the words have
+/// different numbers of letters.  Characters other than letters are
+/// ignored.
+/// ```
+/// use puzzletools::value::add_words;
+/// assert_eq!(add_words("ABC", "aaz"), Some("BCC".to_string()));
+/// assert_eq!(add_words("HAL", "AAA"), Some("IBM".to_string()));
+/// assert_eq!(add_words("AB", "ABC"), None);
+/// ```
+pub fn add_words<S: Text, T: Text>(s: S, t: T) -> Option<String> {
+    combine_words(s, t, |a, b| a + b)
+}
+
+/// Subtracts the letters of `t` from the letters of `s`, modulo 26.
+/// ```
+/// use puzzletools::value::subtract_words;
+/// assert_eq!(subtract_words("IBM", "HAL"), Some("AAA".to_string()));
+/// ```
+pub fn subtract_words<S: Text, T: Text>(s: S, t: T) -> Option<String> {
+    combine_words(s, t, |a, b| a + 26 - b)
+}
+
+/// Three words where the letterwise sum of the first two is the third.
+#[derive(Clone, Copy, Debug)]
+pub struct WordSum<'a> {
+    pub first: &'a WordlistEntry,
+    pub second: &'a WordlistEntry,
+    pub sum: &'a WordlistEntry,
+}
+
+impl SearchResult for WordSum<'_> {
+    type Data = String;
+    type Freq = u64;
+    fn data(&self) -> String {
+        format!(
+            "{} + {} = {}",
+            self.first.word, self.second.word, self.sum.word
+        )
+    }
+    /// The frequency of the least common of the three words.
+    fn freq(&self) -> u64 {
+        self.first.freq.min(self.second.freq).min(self.sum.freq)
+    }
+}
+
+/// Finds the triples of words in which the letterwise sum (see
+/// `add_words`) of the first two words is the third.  The first two
+/// words are taken from `words`, and the sum is looked up in `wl`.
+/// Each pair is only reported once, with the words in the order in which
+/// they appear in `words`.  Since this checks every pair of words of the
+/// same length, `words` should be restricted to a manageable size.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::search::SearchResult;
+/// use puzzletools::value::word_sums;
+/// use puzzletools::wordlist::Wordlist;
+/// let wl = Wordlist::load_from_reader(Cursor::new("HAL,10\nAAA,5\nIBM,8\nDOG,3")).unwrap();
+/// let v: Vec<_> = word_sums(&wl, &wl).map(|s| s.data()).collect();
+/// assert_eq!(v, vec!["HAL + AAA = IBM"]);
+/// ```
+pub fn word_sums<'a, I>(words: I, wl: &'a Wordlist) -> impl Iterator<Item = WordSum<'a>>
+where
+    I: IntoIterator<Item = &'a WordlistEntry>,
+{
+    let mut by_len: HashMap<usize, Vec<&'a WordlistEntry>> = HashMap::new();
+    for w in words {
+        by_len.entry(w.len()).or_default().push(w);
+    }
+    let mut results = Vec::new();
+    for group in by_len.values() {
+        for (i, &first) in group.iter().enumerate() {
+            for &second in &group[i..] {
+                let sum = add_words(&first.slug, &second.slug).unwrap();
+                if let Some(sum) = wl.get(&sum) {
+                    results.push(WordSum { first, second, sum });
+                }
+            }
+        }
+    }
+    sort_results(results.into_iter())
+}
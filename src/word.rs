@@ -1,12 +1,12 @@
 //! Utilities for working with individual words.
 
-use crate::letter::lett_to_num_0;
-use regex::Regex;
-use std::borrow::Cow;
+pub mod phonetic;
 
-lazy_static! {
-    static ref SLUG_RE: Regex = Regex::new("[^A-Z]").unwrap();
-}
+use crate::letter::{flip, lett_to_num_0, mirror, try_lett_to_num_0, upside_down};
+use crate::letter_counts::LetterCounts;
+use crate::letter_set::LetterSet;
+use std::borrow::{Borrow, Cow};
+use std::fmt::{self, Display};
 
 /// A trait for types that could be interpreted as an ASCII string.
 ///
@@ -39,8 +39,21 @@ lazy_static! {
 /// assert_eq!("TEXT".get_char_1(5), None);
 /// ```
 ///
+/// Byte strings are expected to be ASCII.  If a byte string is not
+/// valid UTF-8, `as_str` (and so `bytes` and `chars`) stops at the first
+/// invalid byte, while `as_bytes`, `len` and `byte` see the whole slice:
+/// ```
+/// use puzzletools::word::Text;
+/// let b = &b"AB\xffC"[..];
+/// assert_eq!(b.as_str(), "AB");
+/// assert_eq!(Text::bytes(&b).count(), 2);
+/// assert_eq!(Text::len(&b), 4);
+/// assert_eq!(b.byte(3), b'C');
+/// ```
 pub trait Text {
-    /// Returns representation of this text as a string slice.
+    /// Returns representation of this text as a string slice.  For byte
+    /// strings that are not valid UTF-8, this is the part before the
+    /// first invalid byte.
     fn as_str(&self) -> &str;
     /// Returns representation of this text as a byte array slice.
     fn as_bytes(&self) -> &[u8] {
@@ -124,11 +137,18 @@ pub trait Text {
     }
     /// Returns a reversed copy of this text.
     fn reversed(&self) -> String {
-        let v: Vec<u8> = self.bytes().rev().collect();
-        unsafe { String::from_utf8_unchecked(v) }
+        self.chars().rev().collect()
     }
 }
 
+// Returns the longest prefix of `b` that is valid UTF-8.
+fn bytes_as_str(b: &[u8]) -> &str {
+    std::str::from_utf8(b).unwrap_or_else(|e| {
+        // the prefix is valid by construction
+        std::str::from_utf8(&b[..e.valid_up_to()]).unwrap()
+    })
+}
+
 macro_rules! text_impl_str {
     () => {
         fn as_str(&self) -> &str {
@@ -140,7 +160,7 @@ macro_rules! text_impl_str {
 macro_rules! text_impl_bytes {
     () => {
         fn as_str(&self) -> &str {
-            unsafe { ::std::str::from_utf8_unchecked(self) }
+            bytes_as_str(self)
         }
         fn as_bytes(&self) -> &[u8] {
             self
@@ -196,24 +216,197 @@ impl Text for &Vec<u8> {
     text_impl_bytes!();
 }
 
-/// Removes spaces and punctuation from a string.  Warning: currently
-/// also removes lowercase letters, so only use this with uppercase strings.
+/// A string that is guaranteed to consist only of the uppercase letters
+/// A through Z.  All of the functions in this crate that accept `Text`
+/// can be used with a `Slug` without risk of panics.
+///
+/// ```
+/// use puzzletools::word::{Slug, Text, alphagram};
+/// let slug = Slug::strip("Don't panic!");
+/// assert_eq!(slug.as_str(), "DONTPANIC");
+/// assert_eq!(alphagram(&slug), "ACDINNOPT");
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Slug(String);
+
+impl Slug {
+    /// Converts `s` to a slug, changing lowercase letters to uppercase and
+    /// removing diacritics (see `normalize`).  Returns `None` if `s`
+    /// contains any character that is not a letter.
+    /// ```
+    /// use puzzletools::word::{Slug, Text};
+    /// assert_eq!(Slug::new("Puzzle").unwrap().as_str(), "PUZZLE");
+    /// assert_eq!(Slug::new("Café").unwrap().as_str(), "CAFE");
+    /// assert!(Slug::new("PUZZLE HUNT").is_none());
+    /// assert!(Slug::new("π").is_none());
+    /// ```
+    pub fn new<S: Text>(s: S) -> Option<Self> {
+        let norm = normalize(s.as_str());
+        if norm.bytes().all(|c| c.is_ascii_uppercase()) {
+            Some(Self(norm.into_owned()))
+        } else {
+            None
+        }
+    }
+
+    /// Converts `s` to a slug, normalizing it (see `normalize`) and
+    /// removing all characters other than letters.
+    /// ```
+    /// use puzzletools::word::{Slug, Text};
+    /// assert_eq!(Slug::strip("Puzzle hunt, 2024").as_str(), "PUZZLEHUNT");
+    /// ```
+    pub fn strip<S: Text>(s: S) -> Self {
+        Self(slugify(s.as_str()).into_owned())
+    }
+
+    /// Returns the numbers of the letters of the slug, so that A is 0.
+    /// ```
+    /// use puzzletools::word::Slug;
+    /// let v: Vec<usize> = Slug::strip("Cab").letter_nums().collect();
+    /// assert_eq!(v, vec![2, 0, 1]);
+    /// ```
+    pub fn letter_nums(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.bytes().map(lett_to_num_0)
+    }
+}
+
+impl Text for Slug {
+    fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Text for &Slug {
+    fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Slug {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Slug {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Slug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Slug> for String {
+    fn from(s: Slug) -> String {
+        s.0
+    }
+}
+
+// The ASCII replacement for a non-ASCII character, if there is one.
+fn fold_char(c: char) -> Option<&'static str> {
+    Some(match c {
+        'À'..='Å' | 'à'..='å' | 'Ā'..='ą' | 'Ǎ' | 'ǎ' => "A",
+        'Æ' | 'æ' => "AE",
+        'Ç' | 'ç' | 'Ć'..='č' => "C",
+        'Ð' | 'ð' | 'Ď'..='đ' => "D",
+        'È'..='Ë' | 'è'..='ë' | 'Ē'..='ě' => "E",
+        'Ĝ'..='ģ' => "G",
+        'Ĥ'..='ħ' => "H",
+        'Ì'..='Ï' | 'ì'..='ï' | 'Ĩ'..='ı' | 'Ǐ' | 'ǐ' => "I",
+        'Ĳ' | 'ĳ' => "IJ",
+        'Ĵ' | 'ĵ' => "J",
+        'Ķ'..='ĸ' => "K",
+        'Ĺ'..='ł' => "L",
+        'Ñ' | 'ñ' | 'Ń'..='ŋ' => "N",
+        'Ò'..='Ö' | 'Ø' | 'ò'..='ö' | 'ø' | 'Ō'..='ő' | 'Ǒ' | 'ǒ' => "O",
+        'Œ' | 'œ' => "OE",
+        'Ŕ'..='ř' => "R",
+        'ß' => "SS",
+        'Ś'..='š' | 'ſ' | 'Ș' | 'ș' => "S",
+        'Ţ'..='ŧ' | 'Ț' | 'ț' => "T",
+        'Þ' | 'þ' => "TH",
+        'Ù'..='Ü' | 'ù'..='ü' | 'Ũ'..='ų' | 'Ǔ' | 'ǔ' => "U",
+        'Ŵ' | 'ŵ' => "W",
+        'Ý' | 'ý' | 'ÿ' | 'Ŷ'..='Ÿ' => "Y",
+        'Ź'..='ž' => "Z",
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => "'",
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => "\"",
+        '\u{2010}'..='\u{2015}' | '\u{2212}' => "-",
+        '\u{2026}' => "...",
+        '\u{00A0}' | '\u{2000}'..='\u{200A}' => " ",
+        // combining diacritical marks
+        '\u{0300}'..='\u{036F}' => "",
+        _ => return None,
+    })
+}
+
+/// Converts a string to uppercase, replaces accented letters and ligatures
+/// with the corresponding ASCII letters, and replaces curly quotes, dashes
+/// and unusual spaces with their ASCII equivalents.  Other non-ASCII
+/// characters are converted to uppercase but otherwise kept.
+/// ```
+/// use puzzletools::word::normalize;
+/// assert_eq!(normalize("Café"), "CAFE");
+/// assert_eq!(normalize("Straße"), "STRASSE");
+/// assert_eq!(normalize("Ætherium"), "AETHERIUM");
+/// assert_eq!(normalize("rock ’n’ roll — live"), "ROCK 'N' ROLL - LIVE");
+/// assert_eq!(normalize("Cafe\u{301}"), "CAFE");
+/// ```
+pub fn normalize<S: Text + ?Sized>(s: &S) -> Cow<'_, str> {
+    let st = s.as_str();
+    if st.bytes().all(|c| c.is_ascii() && !c.is_ascii_lowercase()) {
+        return Cow::Borrowed(st);
+    }
+    let mut v = String::with_capacity(st.len());
+    for c in st.chars() {
+        if c.is_ascii() {
+            v.push(c.to_ascii_uppercase());
+        } else if let Some(f) = fold_char(c) {
+            v.push_str(f);
+        } else {
+            v.extend(c.to_uppercase());
+        }
+    }
+    Cow::Owned(v)
+}
+
+/// Normalizes a string (see `normalize`) and removes everything other
+/// than the letters A through Z.
 /// ```
 /// use puzzletools::word::slugify;
 /// assert_eq!(slugify("ONE 2 THREE"),"ONETHREE");
-///
+/// assert_eq!(slugify("Café au lait"),"CAFEAULAIT");
 /// ```
 pub fn slugify<S: Text + ?Sized>(s: &S) -> Cow<'_, str> {
-    SLUG_RE.replace_all(s.as_str(), "")
+    let st = s.as_str();
+    if st.bytes().all(|c| c.is_ascii_uppercase()) {
+        return Cow::Borrowed(st);
+    }
+    Cow::Owned(
+        normalize(st)
+            .chars()
+            .filter(char::is_ascii_uppercase)
+            .collect(),
+    )
 }
 
-/// Returns the number of (uppercase) letters in an ASCII string.
+/// Returns the number of letters in a string, after normalization (see
+/// `normalize`).
 /// ```
 /// use puzzletools::word::slug_len;
 /// assert_eq!(slug_len("ASCII STRING"),11);
+/// assert_eq!(slug_len("Straße"),7);
 /// ```
 pub fn slug_len<S: Text>(s: S) -> usize {
-    s.bytes().filter(u8::is_ascii_alphabetic).count()
+    if s.as_str().is_ascii() {
+        s.bytes().filter(u8::is_ascii_alphabetic).count()
+    } else {
+        slugify(s.as_str()).len()
+    }
 }
 
 /// Returns the letters of the word in sorted order, so that two words
@@ -226,67 +419,65 @@ pub fn slug_len<S: Text>(s: S) -> usize {
 pub fn alphagram<S: Text>(s: S) -> String {
     let mut copy = s.to_byte_vec();
     copy.sort_unstable();
-    unsafe { String::from_utf8_unchecked(copy) }
+    String::from_utf8(copy).unwrap_or_else(|_| {
+        let mut chars: Vec<char> = s.chars().collect();
+        chars.sort_unstable();
+        chars.into_iter().collect()
+    })
 }
 
 /// Applies a subsitution cipher so that the first letter of the word becomes A,
 /// the second unique letter becomes B, etc.
 ///
 /// Two words have the same ciphergram if and only they can be obtained from
-/// each other via a substitution cipher.
+/// each other via a substitution cipher.  Lowercase letters are treated as
+/// uppercase, and characters other than letters are kept.
 /// ```
 /// use puzzletools::word::ciphergram;
 /// assert_eq!(ciphergram("POTATO"),ciphergram("UNEVEN"));
+/// assert_eq!(ciphergram("Don't"),"ABC'D");
 /// ```
 pub fn ciphergram<S: Text>(s: S) -> String {
-    let mut seen = [0xFFu8; 26];
+    let mut seen = [None; 26];
     let mut count = 0u8;
-    for c in s.bytes() {
-        let idx = lett_to_num_0(c);
-        if (seen[idx] as i8) < 0 {
-            seen[idx] = count;
-            count += 1;
-        }
-    }
-    let v = s.bytes().map(|c| b'A' + seen[lett_to_num_0(c)]).collect();
-    unsafe { String::from_utf8_unchecked(v) }
+    s.chars()
+        .map(|c| match try_lett_to_num_0(c) {
+            Some(idx) => {
+                let n = *seen[idx].get_or_insert_with(|| {
+                    count += 1;
+                    count - 1
+                });
+                (b'A' + n) as char
+            }
+            None => c,
+        })
+        .collect()
 }
 
-/// Returns true if all letters in a word are different.
+/// Returns true if all letters in a word are different.  Lowercase letters
+/// are treated as uppercase, and other characters are ignored.
 /// ```
 /// use puzzletools::word::all_unique_letters;
 /// assert!(all_unique_letters("THUNDERCLAPS"));
+/// assert!(all_unique_letters("Thunder claps!"));
 /// assert!(!all_unique_letters("LETTERS"));
 /// ```
 pub fn all_unique_letters<S: Text>(s: S) -> bool {
-    let mut seen = 0u32;
-    for c in s.bytes() {
-        let p = 1u32 << (c - b'A');
-        if seen & p == 0 {
-            seen |= p;
-        } else {
-            return false;
-        }
-    }
-    true
+    let mut seen = LetterSet::new();
+    s.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .all(|c| seen.insert(c))
 }
 
-/// Returns the number of unique letters in a word.
+/// Returns the number of unique letters in a word.  Lowercase letters are
+/// treated as uppercase, and other characters are ignored.
 /// ```
 /// use puzzletools::word::num_unique_letters;
 /// assert_eq!(num_unique_letters("LETTERS"),5);
+/// assert_eq!(num_unique_letters("Let's"),4);
 /// ```
 pub fn num_unique_letters<S: Text>(s: S) -> usize {
-    let mut seen = 0u32;
-    let mut count = 0;
-    for c in s.bytes() {
-        let p = 1u32 << (c - b'A');
-        if seen & p == 0 {
-            seen |= p;
-            count += 1;
-        }
-    }
-    count
+    LetterSet::from_text(s).len()
 }
 
 /// Returns `true` if the second word is obtained from the first word
@@ -327,16 +518,24 @@ pub fn double_letters<S: Text>(s: S) -> String {
     let v = s
         .as_bytes()
         .windows(2)
-        .filter_map(|x| if x[0] == x[1] { Some(x[0]) } else { None })
+        .filter_map(|x| {
+            if x[0] == x[1] && x[0].is_ascii() {
+                Some(x[0])
+            } else {
+                None
+            }
+        })
         .collect();
     // mh02_orange is about 10% faster with from_utf8_unchecked
     // than with from_utf8 + unwrap
+    // SAFETY: only ASCII bytes are kept
     unsafe { String::from_utf8_unchecked(v) }
 }
 
 /// Returns a list of bigrams that appear more than once in a word.
 /// (If a bigram appears `n` times in the word, it will appear `n-1`
-/// times in the list;
+/// times in the list.)  Bigrams containing characters other than
+/// uppercase letters are skipped.
 /// ```
 /// use puzzletools::word::repeated_bigrams;
 /// assert_eq!(repeated_bigrams("ONGOING"),vec![*b"NG"]);
@@ -345,6 +544,9 @@ pub fn repeated_bigrams<S: Text>(s: S) -> Vec<[u8; 2]> {
     let mut seen = [false; 676];
     let mut repeated = Vec::new();
     for b in s.as_bytes().windows(2) {
+        if !b[0].is_ascii_uppercase() || !b[1].is_ascii_uppercase() {
+            continue;
+        }
         let idx = 26 * lett_to_num_0(b[0]) + lett_to_num_0(b[1]);
         if seen[idx] {
             repeated.push([b[0], b[1]]);
@@ -403,11 +605,10 @@ impl<S: Text + Copy> DeletedLetterItem<S> {
         self.text.char(self.pos)
     }
     pub fn text(&self) -> String {
-        format!(
-            "{}{}",
-            &self.text.as_str()[..self.pos],
-            &self.text.as_str()[self.pos + 1..]
-        )
+        let b = self.text.as_bytes();
+        let mut v = b[..self.pos].to_vec();
+        v.extend_from_slice(&b[self.pos + 1..]);
+        String::from_utf8_lossy(&v).into_owned()
     }
 }
 
@@ -444,7 +645,8 @@ fn is_intertwine_helper(s: &[u8], pat1: &[u8], pat2: &[u8]) -> bool {
 }
 
 /// Returns the number of letters in `s` that are not in `t`, and the number
-/// of letters that are in `t` but not in `s`
+/// of letters that are in `t` but not in `s`.  Lowercase letters are
+/// treated as uppercase, and other characters are ignored.
 ///
 /// ```
 /// use puzzletools::word::anagram_difference;
@@ -452,36 +654,93 @@ fn is_intertwine_helper(s: &[u8], pat1: &[u8], pat2: &[u8]) -> bool {
 /// assert_eq!(anagram_difference("DIFFERENCE","FIERCEEND"),(1,0));
 /// assert_eq!(anagram_difference("DIFFERENCE","REFINEDFACE"),(0,1));
 /// assert_eq!(anagram_difference("DIFFERENCE","AIRDEFENCE"),(1,1));
+/// ```
 pub fn anagram_difference<S: Text, T: Text>(s: S, t: T) -> (usize, usize) {
-    let alpha_s = alphagram(s);
-    let alpha_t = alphagram(t);
-    let mut si = alpha_s.bytes().peekable();
-    let mut ti = alpha_t.bytes().peekable();
-    let mut uniq_s = 0;
-    let mut uniq_t = 0;
-    while let Some(sc) = si.peek() {
-        if let Some(tc) = ti.peek() {
-            match sc.cmp(tc) {
-                std::cmp::Ordering::Less => {
-                    uniq_s += 1;
-                    si.next();
-                }
-                std::cmp::Ordering::Equal => {
-                    si.next();
-                    ti.next();
-                }
-                std::cmp::Ordering::Greater => {
-                    uniq_t += 1;
-                    ti.next();
-                }
-            }
-        } else {
-            break;
-        }
-    }
-    uniq_s += si.count();
-    uniq_t += ti.count();
-    (uniq_s, uniq_t)
+    LetterCounts::from_text(s).difference(&LetterCounts::from_text(t))
+}
+
+/// Returns `true` if `s` and `t` are anagrams.  Lowercase letters are
+/// treated as uppercase, and other characters are ignored.
+/// ```
+/// use puzzletools::word::is_anagram;
+/// assert!(is_anagram("LISTEN","SILENT"));
+/// assert!(is_anagram("Eleven plus two","twelve plus one"));
+/// assert!(!is_anagram("LISTEN","SILENCE"));
+/// ```
+pub fn is_anagram<S: Text, T: Text>(s: S, t: T) -> bool {
+    LetterCounts::from_text(s) == LetterCounts::from_text(t)
+}
+
+/// Returns `true` if the letters of `t` can be obtained by adding
+/// `additions` letters to the letters of `s` and rearranging.  This is
+/// like `is_addition`, but the letters may be reordered.
+/// ```
+/// use puzzletools::word::is_anagram_addition;
+/// assert!(is_anagram_addition("PORE","PROSE",1));
+/// assert!(!is_anagram_addition("PORE","PROSE",2));
+/// assert!(!is_anagram_addition("PORE","PRISM",1));
+/// ```
+pub fn is_anagram_addition<S: Text, T: Text>(s: S, t: T, additions: usize) -> bool {
+    LetterCounts::from_text(t)
+        .checked_sub(&LetterCounts::from_text(s))
+        .is_some_and(|c| c.len() == additions)
+}
+
+/// Applies `f` to each character of `s`, returning `None` if `f` returns
+/// `None` for any character.
+/// ```
+/// use puzzletools::letter::flip;
+/// use puzzletools::word::map_word;
+/// assert_eq!(map_word("BOXED", flip), Some("BOXED".to_string()));
+/// assert_eq!(map_word("BOXER", flip), None);
+/// ```
+pub fn map_word<S: Text, F: FnMut(char) -> Option<char>>(s: S, f: F) -> Option<String> {
+    s.chars().map(f).collect()
+}
+
+/// Returns what `s` looks like when rotated by 180 degrees, or `None`
+/// if some letter does not look like a letter when rotated (see
+/// `letter::upside_down`).  This can be used with `wordlist::pairs_iter`
+/// to find words that are other words upside down.
+/// ```
+/// use std::io::Cursor;
+/// use puzzletools::word::upside_down_word;
+/// use puzzletools::wordlist::{pairs_iter, Wordlist};
+/// assert_eq!(upside_down_word("MOW"), Some("MOW".to_string()));
+/// assert_eq!(upside_down_word("pod"), Some("pod".to_string()));
+/// assert_eq!(upside_down_word("NOSH"), Some("HSON".to_string()));
+/// let wl = Wordlist::load_from_reader(Cursor::new("SWIMS,3\nNOON,2\nMOM,1\nWOW,1")).unwrap();
+/// let v: Vec<_> = pairs_iter(wl.iter(), &wl, |w| upside_down_word(&w.slug))
+///     .map(|(a, b)| (a.word.as_str(), b.word.as_str()))
+///     .collect();
+/// assert_eq!(v, vec![("SWIMS", "SWIMS"), ("NOON", "NOON"), ("MOM", "WOW"), ("WOW", "MOM")]);
+/// ```
+pub fn upside_down_word<S: Text>(s: S) -> Option<String> {
+    s.chars().rev().map(upside_down).collect()
+}
+
+/// Returns what `s` looks like in a mirror, or `None` if some letter
+/// does not look like a letter when mirrored (see `letter::mirror`).
+/// ```
+/// use puzzletools::word::mirror_word;
+/// assert_eq!(mirror_word("TOMATO"), Some("OTAMOT".to_string()));
+/// assert_eq!(mirror_word("bid"), Some("bid".to_string()));
+/// assert_eq!(mirror_word("BID"), None);
+/// ```
+pub fn mirror_word<S: Text>(s: S) -> Option<String> {
+    s.chars().rev().map(mirror).collect()
+}
+
+/// Returns what `s` looks like when reflected top to bottom, or `None` if
+/// some letter does not look like a letter when reflected (see
+/// `letter::flip`).
+/// ```
+/// use puzzletools::word::flip_word;
+/// assert_eq!(flip_word("DECK"), Some("DECK".to_string()));
+/// assert_eq!(flip_word("MOB"), Some("WOB".to_string()));
+/// ```
+pub fn flip_word<S: Text>(s: S) -> Option<String> {
+    s.chars().map(flip).collect()
 }
 
 #[test]
@@ -516,3 +775,15 @@ fn double_test() {
 fn repeated_test() {
     assert_eq!(repeated_bigrams("APPLEDUMPLING"), vec![*b"PL"]);
 }
+
+#[test]
+fn non_ascii_test() {
+    let bytes = &b"CAF\xC3"[..];
+    assert_eq!(bytes.as_str(), "CAF");
+    assert_eq!("CAFÉ".reversed(), "ÉFAC");
+    assert_eq!(alphagram("ÉTÉ"), "TÉÉ");
+    assert_eq!(ciphergram("Éte"), "ÉAB");
+    assert!(all_unique_letters("naïve"));
+    assert_eq!(double_letters("\u{2000}\u{2000}"), "");
+    assert!(repeated_bigrams("a-b a-b").is_empty());
+}
@@ -0,0 +1,781 @@
+//! Keys that approximate how a word sounds, for finding sound-alike
+//! words without a pronunciation dictionary: Soundex, Double Metaphone,
+//! and consonant skeletons.
+
+use crate::word::{normalize, Text};
+use crate::wordlist::{Wordlist, WordlistEntry, WordlistIndex};
+
+// The Soundex digit for each letter, or 0 for letters that are not
+// coded.
+const SOUNDEX_CODES: &[u8; 26] = b"01230120022455012623010202";
+
+/// Returns the American Soundex code of `s`: its first letter followed by
+/// three digits.  Characters other than letters are ignored, and accents
+/// are removed.  Returns `None` if `s` has no letters.
+/// ```
+/// use puzzletools::word::phonetic::soundex;
+/// assert_eq!(soundex("Robert"), Some("R163".to_string()));
+/// assert_eq!(soundex("Rupert"), Some("R163".to_string()));
+/// assert_eq!(soundex("Ashcraft"), Some("A261".to_string()));
+/// assert_eq!(soundex("Pfister"), Some("P236".to_string()));
+/// assert_eq!(soundex("Lee"), Some("L000".to_string()));
+/// assert_eq!(soundex("42"), None);
+/// ```
+pub fn soundex<S: Text>(s: S) -> Option<String> {
+    let mut letters = letters(s.as_str());
+    let first = letters.next()?;
+    let mut result = String::with_capacity(4);
+    result.push(first as char);
+    let mut last = SOUNDEX_CODES[(first - b'A') as usize];
+    for c in letters {
+        let code = SOUNDEX_CODES[(c - b'A') as usize];
+        match c {
+            // H and W do not separate letters with the same code.
+            b'H' | b'W' => {}
+            _ if code == b'0' => last = code,
+            _ => {
+                if code != last {
+                    result.push(code as char);
+                    if result.len() == 4 {
+                        break;
+                    }
+                }
+                last = code;
+            }
+        }
+    }
+    while result.len() < 4 {
+        result.push('0');
+    }
+    Some(result)
+}
+
+/// Returns the consonants of `s`, in uppercase, with vowels (including
+/// Y) and characters other than letters removed.
+/// ```
+/// use puzzletools::word::phonetic::consonant_skeleton;
+/// assert_eq!(consonant_skeleton("Neptune"), "NPTN");
+/// assert_eq!(consonant_skeleton("Mississippi"), "MSSSSPP");
+/// ```
+pub fn consonant_skeleton<S: Text>(s: S) -> String {
+    letters(s.as_str())
+        .filter(|&c| !is_vowel(c))
+        .map(char::from)
+        .collect()
+}
+
+// The uppercase ASCII letters of `s`, after removing accents.
+fn letters(s: &str) -> impl Iterator<Item = u8> + '_ {
+    normalize(s)
+        .into_owned()
+        .into_bytes()
+        .into_iter()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+}
+
+fn is_vowel(c: u8) -> bool {
+    matches!(c, b'A' | b'E' | b'I' | b'O' | b'U' | b'Y')
+}
+
+const METAPHONE_LEN: usize = 4;
+
+/// Returns the primary and alternate Double Metaphone keys of `s`.  The
+/// two keys are the same unless `s` has a common alternate pronunciation
+/// (for example, from another language).  Words that sound alike usually
+/// share a key.  In the keys, `0` stands for the "th" sound and `X` for
+/// the "sh" or "ch" sound.
+/// ```
+/// use puzzletools::word::phonetic::double_metaphone;
+/// assert_eq!(double_metaphone("Smith"), ("SM0".to_string(), "XMT".to_string()));
+/// assert_eq!(double_metaphone("Schmidt"), ("XMT".to_string(), "SMT".to_string()));
+/// assert_eq!(double_metaphone("Knight"), ("NT".to_string(), "NT".to_string()));
+/// assert_eq!(double_metaphone("Catherine"), double_metaphone("Kathryn"));
+/// ```
+pub fn double_metaphone<S: Text>(s: S) -> (String, String) {
+    let word: Vec<u8> = normalize(s.as_str())
+        .bytes()
+        .filter(|c| c.is_ascii_alphabetic() || *c == b' ')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let word = word.trim_ascii().to_vec();
+    let mut m = Metaphone {
+        slavo_germanic: contains_seq(&word, b"W")
+            || contains_seq(&word, b"K")
+            || contains_seq(&word, b"CZ")
+            || contains_seq(&word, b"WITZ"),
+        word,
+        primary: String::new(),
+        alternate: String::new(),
+    };
+    m.run();
+    m.primary.truncate(METAPHONE_LEN);
+    m.alternate.truncate(METAPHONE_LEN);
+    (m.primary, m.alternate)
+}
+
+fn contains_seq(s: &[u8], pat: &[u8]) -> bool {
+    s.windows(pat.len()).any(|w| w == pat)
+}
+
+struct Metaphone {
+    word: Vec<u8>,
+    slavo_germanic: bool,
+    primary: String,
+    alternate: String,
+}
+
+impl Metaphone {
+    fn len(&self) -> isize {
+        self.word.len() as isize
+    }
+
+    fn last(&self) -> isize {
+        self.len() - 1
+    }
+
+    fn at(&self, i: isize) -> u8 {
+        if i < 0 {
+            0
+        } else {
+            self.word.get(i as usize).copied().unwrap_or(0)
+        }
+    }
+
+    fn vowel_at(&self, i: isize) -> bool {
+        is_vowel(self.at(i))
+    }
+
+    // Returns `true` if one of the strings in `pats` (which all have the
+    // same length) appears starting at position `i`.
+    fn string_at(&self, i: isize, pats: &[&str]) -> bool {
+        if i < 0 {
+            return false;
+        }
+        let i = i as usize;
+        pats.iter()
+            .any(|p| self.word.get(i..i + p.len()) == Some(p.as_bytes()))
+    }
+
+    fn add(&mut self, s: &str) {
+        self.primary.push_str(s);
+        self.alternate.push_str(s);
+    }
+
+    fn add2(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    fn done(&self) -> bool {
+        self.primary.len() >= METAPHONE_LEN && self.alternate.len() >= METAPHONE_LEN
+    }
+
+    fn germanic(&self) -> bool {
+        self.string_at(0, &["VAN ", "VON "]) || self.string_at(0, &["SCH"])
+    }
+
+    fn run(&mut self) {
+        let mut i = if self.string_at(0, &["GN", "KN", "PN", "WR", "PS"]) {
+            1
+        } else {
+            0
+        };
+        if self.at(0) == b'X' {
+            self.add("S");
+            i = 1;
+        }
+        while !self.done() && i <= self.last() {
+            i = match self.at(i) {
+                b'A' | b'E' | b'I' | b'O' | b'U' | b'Y' => {
+                    if i == 0 {
+                        self.add("A");
+                    }
+                    i + 1
+                }
+                b'B' => self.simple(i, "P"),
+                b'C' => self.c(i),
+                b'D' => self.d(i),
+                b'F' => self.simple(i, "F"),
+                b'G' => self.g(i),
+                b'H' => self.h(i),
+                b'J' => self.j(i),
+                b'K' => self.simple(i, "K"),
+                b'L' => self.l(i),
+                b'M' => self.m(i),
+                b'N' => self.simple(i, "N"),
+                b'P' => self.p(i),
+                b'Q' => self.simple(i, "K"),
+                b'R' => self.r(i),
+                b'S' => self.s(i),
+                b'T' => self.t(i),
+                b'V' => self.simple(i, "F"),
+                b'W' => self.w(i),
+                b'X' => self.x(i),
+                b'Z' => self.z(i),
+                _ => i + 1,
+            };
+        }
+    }
+
+    // A letter with a single sound, which is also the sound of the letter
+    // doubled.
+    fn simple(&mut self, i: isize, sound: &str) -> isize {
+        self.add(sound);
+        if self.at(i + 1) == self.at(i) {
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn c(&mut self, i: isize) -> isize {
+        if self.c_is_germanic_ach(i) {
+            self.add("K");
+            i + 2
+        } else if i == 0 && self.string_at(i, &["CAESAR"]) {
+            self.add("S");
+            i + 2
+        } else if self.string_at(i, &["CH"]) {
+            self.ch(i)
+        } else if self.string_at(i, &["CZ"]) && !self.string_at(i - 2, &["WICZ"]) {
+            self.add2("S", "X");
+            i + 2
+        } else if self.string_at(i + 1, &["CIA"]) {
+            self.add("X");
+            i + 3
+        } else if self.string_at(i, &["CC"]) && !(i == 1 && self.at(0) == b'M') {
+            self.cc(i)
+        } else if self.string_at(i, &["CK", "CG", "CQ"]) {
+            self.add("K");
+            i + 2
+        } else if self.string_at(i, &["CI", "CE", "CY"]) {
+            if self.string_at(i, &["CIO", "CIE", "CIA"]) {
+                self.add2("S", "X");
+            } else {
+                self.add("S");
+            }
+            i + 2
+        } else {
+            self.add("K");
+            if self.string_at(i + 1, &[" C", " Q", " G"]) {
+                i + 3
+            } else if self.string_at(i + 1, &["C", "K", "Q"])
+                && !self.string_at(i + 1, &["CE", "CI"])
+            {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    // As in BACHER and MACHER.
+    fn c_is_germanic_ach(&self, i: isize) -> bool {
+        if self.string_at(i, &["CHIA"]) {
+            true
+        } else if i <= 1 || self.vowel_at(i - 2) || !self.string_at(i - 1, &["ACH"]) {
+            false
+        } else {
+            let c = self.at(i + 2);
+            (c != b'I' && c != b'E') || self.string_at(i - 2, &["BACHER", "MACHER"])
+        }
+    }
+
+    fn ch(&mut self, i: isize) -> isize {
+        if i > 0 && self.string_at(i, &["CHAE"]) {
+            self.add2("K", "X");
+        } else if self.ch_is_greek(i) || self.ch_is_k(i) {
+            self.add("K");
+        } else if i > 0 {
+            if self.string_at(0, &["MC"]) {
+                self.add("K");
+            } else {
+                self.add2("X", "K");
+            }
+        } else {
+            self.add("X");
+        }
+        i + 2
+    }
+
+    fn ch_is_greek(&self, i: isize) -> bool {
+        i == 0
+            && (self.string_at(i + 1, &["HARAC", "HARIS"])
+                || self.string_at(i + 1, &["HOR", "HYM", "HIA", "HEM"]))
+            && !self.string_at(0, &["CHORE"])
+    }
+
+    fn ch_is_k(&self, i: isize) -> bool {
+        self.germanic()
+            || self.string_at(i - 2, &["ORCHES", "ARCHIT", "ORCHID"])
+            || self.string_at(i + 2, &["T", "S"])
+            || ((self.string_at(i - 1, &["A", "O", "U", "E"]) || i == 0)
+                && (self.string_at(i + 2, &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "])
+                    || i + 1 == self.last()))
+    }
+
+    fn cc(&mut self, i: isize) -> isize {
+        if self.string_at(i + 2, &["I", "E", "H"]) && !self.string_at(i + 2, &["HU"]) {
+            if (i == 1 && self.at(i - 1) == b'A') || self.string_at(i - 1, &["UCCEE", "UCCES"]) {
+                self.add("KS");
+            } else {
+                self.add("X");
+            }
+            i + 3
+        } else {
+            self.add("K");
+            i + 2
+        }
+    }
+
+    fn d(&mut self, i: isize) -> isize {
+        if self.string_at(i, &["DG"]) {
+            if self.string_at(i + 2, &["I", "E", "Y"]) {
+                self.add("J");
+                i + 3
+            } else {
+                self.add("TK");
+                i + 2
+            }
+        } else if self.string_at(i, &["DT", "DD"]) {
+            self.add("T");
+            i + 2
+        } else {
+            self.add("T");
+            i + 1
+        }
+    }
+
+    fn g(&mut self, i: isize) -> isize {
+        let next = self.at(i + 1);
+        if next == b'H' {
+            self.gh(i)
+        } else if next == b'N' {
+            if i == 1 && self.vowel_at(0) && !self.slavo_germanic {
+                self.add2("KN", "N");
+            } else if !self.string_at(i + 2, &["EY"]) && !self.slavo_germanic {
+                self.add2("N", "KN");
+            } else {
+                self.add("KN");
+            }
+            i + 2
+        } else if self.string_at(i + 1, &["LI"]) && !self.slavo_germanic {
+            self.add2("KL", "L");
+            i + 2
+        } else if self.g_is_k_or_j(i) {
+            self.add2("K", "J");
+            i + 2
+        } else if self.string_at(i + 1, &["E", "I", "Y"])
+            || self.string_at(i - 1, &["AGGI", "OGGI"])
+        {
+            if self.germanic() || self.string_at(i + 1, &["ET"]) {
+                self.add("K");
+            } else if self.string_at(i + 1, &["IER"]) {
+                self.add("J");
+            } else {
+                self.add2("J", "K");
+            }
+            i + 2
+        } else if next == b'G' {
+            self.add("K");
+            i + 2
+        } else {
+            self.add("K");
+            i + 1
+        }
+    }
+
+    // As in GESTALT and GERMAN (but not DANGER or BIOLOGY).
+    fn g_is_k_or_j(&self, i: isize) -> bool {
+        let next = self.at(i + 1);
+        if i == 0 {
+            next == b'Y'
+                || self.string_at(
+                    i + 1,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                )
+        } else {
+            (self.string_at(i + 1, &["ER"]) || next == b'Y')
+                && !self.string_at(0, &["DANGER", "RANGER", "MANGER"])
+                && !self.string_at(i - 1, &["E", "I"])
+                && !self.string_at(i - 1, &["RGY", "OGY"])
+        }
+    }
+
+    fn gh(&mut self, i: isize) -> isize {
+        if i > 0 && !self.vowel_at(i - 1) {
+            self.add("K");
+        } else if i == 0 {
+            if self.at(i + 2) == b'I' {
+                self.add("J");
+            } else {
+                self.add("K");
+            }
+        } else if (i > 1 && self.string_at(i - 2, &["B", "H", "D"]))
+            || (i > 2 && self.string_at(i - 3, &["B", "H", "D"]))
+            || (i > 3 && self.string_at(i - 4, &["B", "H"]))
+        {
+            // Silent, as in BOUGH and BROUGHTON.
+        } else if i > 2
+            && self.at(i - 1) == b'U'
+            && self.string_at(i - 3, &["C", "G", "L", "R", "T"])
+        {
+            // As in LAUGH and TOUGH.
+            self.add("F");
+        } else if i > 0 && self.at(i - 1) != b'I' {
+            self.add("K");
+        }
+        i + 2
+    }
+
+    fn h(&mut self, i: isize) -> isize {
+        if (i == 0 || self.vowel_at(i - 1)) && self.vowel_at(i + 1) {
+            self.add("H");
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn j(&mut self, i: isize) -> isize {
+        if self.string_at(i, &["JOSE"]) || self.string_at(0, &["SAN "]) {
+            if (i == 0 && self.at(i + 4) == b' ') || self.len() == 4 || self.string_at(0, &["SAN "])
+            {
+                self.add("H");
+            } else {
+                self.add2("J", "H");
+            }
+            return i + 1;
+        }
+        if i == 0 {
+            self.add2("J", "A");
+        } else if self.vowel_at(i - 1)
+            && !self.slavo_germanic
+            && matches!(self.at(i + 1), b'A' | b'O')
+        {
+            self.add2("J", "H");
+        } else if i == self.last() {
+            self.add2("J", "");
+        } else if !self.string_at(i + 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.string_at(i - 1, &["S", "K", "L"])
+        {
+            self.add("J");
+        }
+        if self.at(i + 1) == b'J' {
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn l(&mut self, i: isize) -> isize {
+        if self.at(i + 1) == b'L' {
+            // Spanish, as in CABRILLO and GALLEGOS.
+            let spanish = (i == self.len() - 3 && self.string_at(i - 1, &["ILLO", "ILLA", "ALLE"]))
+                || ((self.string_at(self.len() - 2, &["AS", "OS"])
+                    || self.string_at(self.last(), &["A", "O"]))
+                    && self.string_at(i - 1, &["ALLE"]));
+            if spanish {
+                self.add2("L", "");
+            } else {
+                self.add("L");
+            }
+            i + 2
+        } else {
+            self.add("L");
+            i + 1
+        }
+    }
+
+    fn m(&mut self, i: isize) -> isize {
+        self.add("M");
+        // As in DUMB and THUMB.
+        if self.at(i + 1) == b'M'
+            || (self.string_at(i - 1, &["UMB"])
+                && (i + 1 == self.last() || self.string_at(i + 2, &["ER"])))
+        {
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn p(&mut self, i: isize) -> isize {
+        if self.at(i + 1) == b'H' {
+            self.add("F");
+            i + 2
+        } else {
+            self.add("P");
+            if self.string_at(i + 1, &["P", "B"]) {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    fn r(&mut self, i: isize) -> isize {
+        // French, as in ROGIER.
+        if i == self.last()
+            && !self.slavo_germanic
+            && self.string_at(i - 2, &["IE"])
+            && !self.string_at(i - 4, &["ME", "MA"])
+        {
+            self.add2("", "R");
+        } else {
+            self.add("R");
+        }
+        if self.at(i + 1) == b'R' {
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn s(&mut self, i: isize) -> isize {
+        if self.string_at(i - 1, &["ISL", "YSL"]) {
+            // Silent, as in ISLAND.
+            i + 1
+        } else if i == 0 && self.string_at(i, &["SUGAR"]) {
+            self.add2("X", "S");
+            i + 1
+        } else if self.string_at(i, &["SH"]) {
+            if self.string_at(i + 1, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.add("S");
+            } else {
+                self.add("X");
+            }
+            i + 2
+        } else if self.string_at(i, &["SIO", "SIA"]) || self.string_at(i, &["SIAN"]) {
+            if self.slavo_germanic {
+                self.add("S");
+            } else {
+                self.add2("S", "X");
+            }
+            i + 3
+        } else if (i == 0 && self.string_at(i + 1, &["M", "N", "L", "W"]))
+            || self.string_at(i + 1, &["Z"])
+        {
+            self.add2("S", "X");
+            if self.string_at(i + 1, &["Z"]) {
+                i + 2
+            } else {
+                i + 1
+            }
+        } else if self.string_at(i, &["SC"]) {
+            self.sc(i)
+        } else {
+            // French, as in RESNAIS.
+            if i == self.last() && self.string_at(i - 2, &["AI", "OI"]) {
+                self.add2("", "S");
+            } else {
+                self.add("S");
+            }
+            if self.string_at(i + 1, &["S", "Z"]) {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    fn sc(&mut self, i: isize) -> isize {
+        if self.at(i + 2) == b'H' {
+            if self.string_at(i + 3, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                if self.string_at(i + 3, &["ER", "EN"]) {
+                    self.add2("X", "SK");
+                } else {
+                    self.add("SK");
+                }
+            } else if i == 0 && !self.vowel_at(3) && self.at(3) != b'W' {
+                self.add2("X", "S");
+            } else {
+                self.add("X");
+            }
+        } else if self.string_at(i + 2, &["I", "E", "Y"]) {
+            self.add("S");
+        } else {
+            self.add("SK");
+        }
+        i + 3
+    }
+
+    fn t(&mut self, i: isize) -> isize {
+        if self.string_at(i, &["TION"]) || self.string_at(i, &["TIA", "TCH"]) {
+            self.add("X");
+            i + 3
+        } else if self.string_at(i, &["TH"]) || self.string_at(i, &["TTH"]) {
+            if self.string_at(i + 2, &["OM", "AM"]) || self.germanic() {
+                self.add("T");
+            } else {
+                self.add2("0", "T");
+            }
+            i + 2
+        } else {
+            self.add("T");
+            if self.string_at(i + 1, &["T", "D"]) {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    fn w(&mut self, i: isize) -> isize {
+        if self.string_at(i, &["WR"]) {
+            self.add("R");
+            i + 2
+        } else if i == 0 && (self.vowel_at(i + 1) || self.string_at(i, &["WH"])) {
+            if self.vowel_at(i + 1) {
+                self.add2("A", "F");
+            } else {
+                self.add("A");
+            }
+            i + 1
+        } else if (i == self.last() && self.vowel_at(i - 1))
+            || self.string_at(i - 1, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.string_at(0, &["SCH"])
+        {
+            // Polish, as in FILIPOWICZ.
+            self.add2("", "F");
+            i + 1
+        } else if self.string_at(i, &["WICZ", "WITZ"]) {
+            self.add2("TS", "FX");
+            i + 4
+        } else {
+            i + 1
+        }
+    }
+
+    fn x(&mut self, i: isize) -> isize {
+        if i == 0 {
+            self.add("S");
+            return i + 1;
+        }
+        // French, as in BREAUX.
+        if !(i == self.last()
+            && (self.string_at(i - 3, &["IAU", "EAU"]) || self.string_at(i - 2, &["AU", "OU"])))
+        {
+            self.add("KS");
+        }
+        if self.string_at(i + 1, &["C", "X"]) {
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn z(&mut self, i: isize) -> isize {
+        if self.at(i + 1) == b'H' {
+            // Chinese, as in ZHAO.
+            self.add("J");
+            return i + 2;
+        }
+        if self.string_at(i + 1, &["ZO", "ZI", "ZA"])
+            || (self.slavo_germanic && i > 0 && self.at(i - 1) != b'T')
+        {
+            self.add2("S", "TS");
+        } else {
+            self.add("S");
+        }
+        if self.at(i + 1) == b'Z' {
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+}
+
+/// A kind of phonetic key.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PhoneticKey {
+    /// See `soundex`.
+    Soundex,
+    /// Both keys returned by `double_metaphone`.
+    DoubleMetaphone,
+    /// See `consonant_skeleton`.
+    ConsonantSkeleton,
+}
+
+impl PhoneticKey {
+    /// Returns the keys of this kind for `s`.  There can be zero keys
+    /// (if `s` has no letters), one key, or, for Double Metaphone, two.
+    /// ```
+    /// use puzzletools::word::phonetic::PhoneticKey;
+    /// assert_eq!(PhoneticKey::Soundex.keys("Tymczak"), vec!["T522"]);
+    /// assert_eq!(PhoneticKey::DoubleMetaphone.keys("Smith"), vec!["SM0", "XMT"]);
+    /// assert_eq!(PhoneticKey::DoubleMetaphone.keys("Knight"), vec!["NT"]);
+    /// ```
+    pub fn keys<S: Text>(&self, s: S) -> Vec<String> {
+        match self {
+            PhoneticKey::Soundex => soundex(s).into_iter().collect(),
+            PhoneticKey::DoubleMetaphone => {
+                let (primary, alternate) = double_metaphone(s);
+                let mut v = vec![primary];
+                if alternate != v[0] {
+                    v.push(alternate);
+                }
+                v.retain(|k| !k.is_empty());
+                v
+            }
+            PhoneticKey::ConsonantSkeleton => {
+                let skeleton = consonant_skeleton(s);
+                if skeleton.is_empty() {
+                    vec![]
+                } else {
+                    vec![skeleton]
+                }
+            }
+        }
+    }
+}
+
+/// An index of the entries of a wordlist by a phonetic key, for finding
+/// words that sound like a given word.
+pub struct PhoneticIndex<'a> {
+    kind: PhoneticKey,
+    index: WordlistIndex<'a, String>,
+}
+
+impl<'a> PhoneticIndex<'a> {
+    /// Builds an index of the entries of `wl` by the given kind of key.
+    pub fn new(wl: &'a Wordlist, kind: PhoneticKey) -> Self {
+        PhoneticIndex {
+            kind,
+            index: wl.index_by(|e| kind.keys(e.word.as_str())),
+        }
+    }
+
+    /// Returns the entries that share a key with `s`, from most to least
+    /// frequent.  The result may include `s` itself.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::word::phonetic::{PhoneticIndex, PhoneticKey};
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("NIGHT,10\nKNIGHT,5\nNOTE,3\nDAY,2")).unwrap();
+    /// let index = PhoneticIndex::new(&wl, PhoneticKey::DoubleMetaphone);
+    /// let v: Vec<_> = index.sounds_like("knit").iter().map(|e| e.word.as_str()).collect();
+    /// assert_eq!(v, vec!["NIGHT", "KNIGHT", "NOTE"]);
+    /// ```
+    pub fn sounds_like<S: Text>(&self, s: S) -> Vec<&'a WordlistEntry> {
+        let mut v: Vec<&'a WordlistEntry> = self
+            .kind
+            .keys(s)
+            .iter()
+            .flat_map(|k| self.index.get(k.as_str()))
+            .copied()
+            .collect();
+        // slugs are unique, so copies of an entry end up next to each other
+        v.sort_by(|a, b| b.freq.cmp(&a.freq).then_with(|| a.slug.cmp(&b.slug)));
+        v.dedup_by(|a, b| std::ptr::eq(*a, *b));
+        v
+    }
+
+    /// The underlying index, keyed by phonetic key.
+    pub fn index(&self) -> &WordlistIndex<'a, String> {
+        &self.index
+    }
+}
@@ -1,15 +1,20 @@
 //! Utilities for searching or iterating through a word list.
 #![allow(clippy::len_without_is_empty)]
 use crate::error::Result;
+use crate::letter_set::LetterSet;
 use crate::search::SearchResult;
-use crate::word::{slug_len, slugify, Text};
-use std::borrow::Cow;
+use crate::word::{normalize, slug_len, slugify, Text};
+use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
 use std::fs::File;
-use std::hash::BuildHasher;
+use std::hash::{BuildHasher, Hash};
 use std::io::{BufReader, Read};
 use std::iter::FromIterator;
 use std::path::PathBuf;
 
+// The longest word considered by `Wordlist::segment`.
+const MAX_WORD_LEN: usize = 30;
+
 pub fn load_wordlist_file(name: &str) -> Result<BufReader<File>> {
     dotenvy::dotenv().ok();
     let mut path = dotenvy::var("WORDLIST_DIR").map_or_else(|_| PathBuf::new(), PathBuf::from);
@@ -61,15 +66,20 @@ impl WordFreq {
     /// use puzzletools::wordlist::WordFreq;
     /// let wf = WordFreq { word: "ASCII STRING".to_owned(), freq: 1 };
     /// assert_eq!(wf.slug(),"ASCIISTRING");
+    /// let wf = WordFreq { word: "NAÏVE".to_owned(), freq: 1 };
+    /// assert_eq!(wf.slug(),"NAIVE");
     /// ```
     pub fn slug(&self) -> Cow<'_, str> {
         slugify(&self.word)
     }
-    /// The number of letters in the word (non-alphabetic characters are not counted).
+    /// The number of letters in the slug of the word (non-alphabetic
+    /// characters are not counted).
     /// ```
     /// use puzzletools::wordlist::WordFreq;
     /// let wf = WordFreq { word: "ASCII STRING".to_owned(), freq: 1 };
     /// assert_eq!(wf.len(),11);
+    /// let wf = WordFreq { word: "ÆSIR".to_owned(), freq: 1 };
+    /// assert_eq!(wf.len(),5);
     /// ```
     pub fn len(&self) -> usize {
         slug_len(&self.word)
@@ -159,6 +169,9 @@ impl SearchResult for (&WordFreq, &WordlistEntry) {
 /// need to do lookups, it is faster to use `wordlist_iter`.
 pub struct Wordlist {
     entries: Vec<WordlistEntry>,
+    // the letters of each entry, in the same order as `entries`
+    letter_sets: Vec<LetterSet>,
+    total_freq: u64,
     // this is essentially a manually implemented IndexSet,
     // but for some reason was about 30% faster in tests
     lookup: hashbrown::HashTable<usize>,
@@ -166,6 +179,16 @@ pub struct Wordlist {
 }
 
 impl Wordlist {
+    /// Looks up a slug.  The slug must match exactly, so lowercase
+    /// letters, punctuation and other non-letters never match an entry.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("IT,3")).unwrap();
+    /// assert_eq!(wl.get("IT").unwrap().word, "IT");
+    /// assert!(wl.get("it").is_none());
+    /// assert!(wl.get("I#T").is_none());
+    /// ```
     pub fn get<S: Text>(&self, s: S) -> Option<&WordlistEntry> {
         let bytes = s.as_bytes();
         let hash = self.hasher.hash_one(bytes);
@@ -174,6 +197,21 @@ impl Wordlist {
             .map(|&n| &self.entries[n])
     }
 
+    /// Looks up a word after normalizing it (see `normalize`), so case
+    /// and diacritics are ignored.  Any other characters must match the
+    /// slug exactly.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("CAFÉ,3\nSTRASSE,2")).unwrap();
+    /// assert_eq!(wl.get_normalized("café").unwrap().word, "CAFÉ");
+    /// assert_eq!(wl.get_normalized("Straße").unwrap().word, "STRASSE");
+    /// assert!(wl.get_normalized("ca fé").is_none());
+    /// ```
+    pub fn get_normalized<S: Text>(&self, s: S) -> Option<&WordlistEntry> {
+        self.get(normalize(&s).as_ref())
+    }
+
     /// Returns the frequency of the given slug, or zero if the slug
     /// does not appear in the wordlist.
     /// ```
@@ -188,6 +226,86 @@ impl Wordlist {
         self.get(s).map_or(0, |e| e.freq)
     }
 
+    /// Returns the sum of the frequencies of all words in the wordlist.
+    pub fn total_freq(&self) -> u64 {
+        self.total_freq
+    }
+
+    // Finds the most likely way to split `s` into words, treating the
+    // frequencies as probabilities.  If `unknown` is given, letters that
+    // are not part of any word are allowed, at a cost of `unknown` per
+    // letter.  Returns the log (base 10) probability of the best split and
+    // the start of the last piece ending at each position.
+    fn segmentation(&self, s: &[u8], unknown: Option<f64>) -> (f64, Vec<usize>) {
+        let log_total = (self.total_freq.max(1) as f64).log10();
+        let mut best = vec![f64::NEG_INFINITY; s.len() + 1];
+        let mut back = vec![0; s.len() + 1];
+        best[0] = 0.0;
+        for end in 1..=s.len() {
+            if let Some(u) = unknown {
+                best[end] = best[end - 1] + u;
+                back[end] = end - 1;
+            }
+            for start in end.saturating_sub(MAX_WORD_LEN)..end {
+                if best[start] == f64::NEG_INFINITY {
+                    continue;
+                }
+                if let Some(e) = self.get(&s[start..end]) {
+                    let score = best[start] + (e.freq.max(1) as f64).log10() - log_total;
+                    if score > best[end] {
+                        best[end] = score;
+                        back[end] = start;
+                    }
+                }
+            }
+        }
+        (best[s.len()], back)
+    }
+
+    /// Splits a string of uppercase letters into words from the wordlist,
+    /// choosing the split that maximizes the product of the frequencies of
+    /// the words.  Returns `None` if there is no way to split the string.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("A,5\nPEN,3\nPENIS,1\nIS,4\nLAND,2\nISLAND,2")).unwrap();
+    /// let words: Vec<_> = wl.segment("APENISLAND").unwrap().iter().map(|e| e.word.as_str()).collect();
+    /// assert_eq!(words, vec!["A", "PEN", "ISLAND"]);
+    /// assert!(wl.segment("PENS").is_none());
+    /// ```
+    pub fn segment<S: Text>(&self, s: S) -> Option<Vec<&WordlistEntry>> {
+        let s = s.as_bytes();
+        let (score, back) = self.segmentation(s, None);
+        if score == f64::NEG_INFINITY {
+            return None;
+        }
+        let mut v = Vec::new();
+        let mut end = s.len();
+        while end > 0 {
+            let start = back[end];
+            v.push(self.get(&s[start..end])?);
+            end = start;
+        }
+        v.reverse();
+        Some(v)
+    }
+
+    /// Returns a measure of how likely it is that the given uppercase
+    /// letters are English text.  This is the log (base 10) probability
+    /// of the best way of splitting the letters into words, where letters
+    /// that are not part of any word are penalized heavily.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("HELLO,10\nWORLD,10")).unwrap();
+    /// assert!(wl.segmentation_score("HELLOWORLD") > wl.segmentation_score("HELLOWORLF"));
+    /// assert!(wl.segmentation_score("HELLOWORLF") > wl.segmentation_score("HELLPWORLF"));
+    /// ```
+    pub fn segmentation_score<S: Text>(&self, s: S) -> f64 {
+        let unknown = -(self.total_freq.max(1) as f64).log10() - 1.0;
+        self.segmentation(s.as_bytes(), Some(unknown)).0
+    }
+
     pub fn load_from_reader<R: Read>(r: R) -> Result<Self> {
         let mut rdr = csv::ReaderBuilder::new().has_headers(false).from_reader(r);
         let res: ::std::result::Result<Self, csv::Error> = rdr.deserialize::<WordFreq>().collect();
@@ -202,6 +320,103 @@ impl Wordlist {
     pub fn iter(&self) -> ::std::slice::Iter<'_, WordlistEntry> {
         self.into_iter()
     }
+
+    /// Iterates over the entries of the wordlist along with the sets of
+    /// letters they contain.  The sets are computed when the wordlist is
+    /// loaded.
+    pub fn iter_letter_sets(&self) -> impl Iterator<Item = (&WordlistEntry, LetterSet)> {
+        self.entries.iter().zip(self.letter_sets.iter().copied())
+    }
+
+    /// Returns the entries that contain all of the letters in `required`
+    /// and none of the letters in `forbidden`.
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::letter_set::LetterSet;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new(
+    ///     "EDUCATION,5\nFACETIOUS,3\nWHEEL,8\nSEQUOIA,2",
+    /// )).unwrap();
+    /// let vowels = LetterSet::from_text("AEIOU");
+    /// let v: Vec<_> = wl.filter_letters(vowels, LetterSet::EMPTY).map(|e| e.word.as_str()).collect();
+    /// assert_eq!(v, vec!["EDUCATION", "FACETIOUS", "SEQUOIA"]);
+    /// let v: Vec<_> = wl
+    ///     .filter_letters(LetterSet::EMPTY, LetterSet::from_text("SPORT"))
+    ///     .map(|e| e.word.as_str())
+    ///     .collect();
+    /// assert_eq!(v, vec!["WHEEL"]);
+    /// ```
+    pub fn filter_letters(
+        &self,
+        required: LetterSet,
+        forbidden: LetterSet,
+    ) -> impl Iterator<Item = &WordlistEntry> {
+        self.iter_letter_sets()
+            .filter(move |(_, s)| s.is_superset(&required) && s.is_disjoint(&forbidden))
+            .map(|(e, _)| e)
+    }
+
+    /// Builds an index of the entries of the wordlist by the keys returned
+    /// by `keys`.  An entry can have any number of keys (`Option` works
+    /// for zero or one key).
+    /// ```
+    /// use std::io::Cursor;
+    /// use puzzletools::word::alphagram;
+    /// use puzzletools::wordlist::Wordlist;
+    /// let wl = Wordlist::load_from_reader(Cursor::new("STAR,1\nRATS,3\nMOON,2")).unwrap();
+    /// let index = wl.index_by(|e| Some(alphagram(&e.slug)));
+    /// let words: Vec<_> = index.get("ARST").iter().map(|e| e.word.as_str()).collect();
+    /// assert_eq!(words, vec!["RATS", "STAR"]);
+    /// assert!(index.get("ABC").is_empty());
+    /// ```
+    pub fn index_by<K, F, I>(&self, mut keys: F) -> WordlistIndex<'_, K>
+    where
+        K: Eq + Hash,
+        F: FnMut(&WordlistEntry) -> I,
+        I: IntoIterator<Item = K>,
+    {
+        let mut map: HashMap<K, Vec<&WordlistEntry>> = HashMap::new();
+        for e in self {
+            for k in keys(e) {
+                let v = map.entry(k).or_default();
+                if !v.last().is_some_and(|&l| std::ptr::eq(l, e)) {
+                    v.push(e);
+                }
+            }
+        }
+        for v in map.values_mut() {
+            v.sort_by_key(|e| std::cmp::Reverse(e.freq));
+        }
+        WordlistIndex { map }
+    }
+}
+
+/// An index of the entries of a wordlist by some key, built by
+/// `Wordlist::index_by`.
+pub struct WordlistIndex<'a, K> {
+    map: HashMap<K, Vec<&'a WordlistEntry>>,
+}
+
+impl<'a, K: Eq + Hash> WordlistIndex<'a, K> {
+    /// Returns the entries with the given key, from most to least
+    /// frequent.
+    pub fn get<Q>(&self, key: &Q) -> &[&'a WordlistEntry]
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the keys of the index, along with their entries.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &[&'a WordlistEntry])> {
+        self.map.iter().map(|(k, v)| (k, v.as_slice()))
+    }
+
+    /// The number of distinct keys.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
 }
 
 impl<'a> IntoIterator for &'a Wordlist {
@@ -225,8 +440,15 @@ impl FromIterator<WordlistEntry> for Wordlist {
             let hash = hasher.hash_one(item.slug.as_bytes());
             lookup.insert_unique(hash, n, |&n| hasher.hash_one(entries[n].slug.as_bytes()));
         }
+        let total_freq = entries.iter().map(|e| e.freq).sum();
+        let letter_sets = entries
+            .iter()
+            .map(|e| LetterSet::from_text(&e.slug))
+            .collect();
         Self {
             entries,
+            letter_sets,
+            total_freq,
             lookup,
             hasher,
         }
@@ -346,3 +568,10 @@ where
             .filter_map(move |wt: J::Item| list2.get(wt).map(|w2| (w1.to_owned(), w2)))
     })
 }
+
+#[test]
+fn get_exact_test() {
+    let wl = Wordlist::load_from_reader(std::io::Cursor::new("IT,3\nCAFÉ,2")).unwrap();
+    assert!(wl.get("I#T").is_none());
+    assert_eq!(wl.get_normalized("café").unwrap().word, "CAFÉ");
+}